@@ -0,0 +1,44 @@
+//! Two overlapping, half-transparent rects, for checking that
+//! translucent sprites blend with each other.
+//!
+//! The red rect is in front, but is spawned first. Where they
+//! overlap, both should show through as purple; if the red one
+//! hid the blue one instead, depth testing would be back on.
+
+use genji::prelude::*;
+
+#[genji::init]
+type State = GameState<()>;
+
+fn init() -> (State, World) {
+    let mut world = World::new();
+
+    world.spawn((
+        shape::rect(120, 120),
+        Point(-40, 20),
+        Color::new(255, 0, 0, 128),
+        Depth(1),
+    ));
+    world.spawn((
+        shape::rect(120, 120),
+        Point(40, -20),
+        Color::new(0, 0, 255, 128),
+        Depth(2),
+    ));
+
+    let state = State::new(
+        (),
+        "Translucent sprites",
+        None,
+        None,
+        None,
+        Some(Color::new(255, 255, 255, 255)),
+    );
+    (state, world)
+}
+
+fn onloop(state: &mut State, _world: &mut World, _audio: &mut Audio) -> bool {
+    state.keys[Key::Esc]
+}
+
+fn close(_state: State, _world: World, _audio: &mut Audio) {}
@@ -1,5 +1,5 @@
 
 fn main() {
-    genji::main(init, onloop, close);
+    genji::main(init, onloop, close, ON_EVENT, POST_RENDER, LOADING);
 }
 
@@ -11,10 +11,83 @@ use proc_macro::TokenStream;
 /// type State = GameState<()>;
 /// ```
 ///
+/// To see raw window and input events before genji
+/// handles them, pass `on_event`, and write a function
+/// with that name. Returning true from it stops genji from
+/// handling the event itself. Events sent through
+/// `state.event_proxy()` arrive as `Event::UserEvent`.
+/// ```
+/// # use genji::prelude::*;
+/// # use genji::input::{event::Event, UserEvent};
+///
+/// #[genji::init(on_event)]
+/// type State = GameState<()>;
+///
+/// fn on_event(state: &mut State, world: &mut World, event: &Event<UserEvent>) -> bool {
+///     false
+/// }
+/// ```
+///
+/// Similarly, pass `post_render` to run a function after
+/// every frame is drawn and presented (e.g. for measuring
+/// frame timing). Both can be passed at once.
+/// ```
+/// # use genji::prelude::*;
+///
+/// #[genji::init(on_event, post_render)]
+/// type State = GameState<()>;
+///
+/// fn post_render(state: &mut State) {}
+/// ```
+///
+/// Pass `loading` to load assets on a separate thread while
+/// a loading screen runs, instead of freezing the window.
+/// This needs three more functions (see `genji::loading`).
+/// ```
+/// # use genji::{prelude::*, loading::Progress};
+///
+/// #[genji::init(loading)]
+/// type State = GameState<()>;
+///
+/// fn load(progress: &Progress) -> Vec<u8> {
+///     Vec::new()
+/// }
+///
+/// fn loading_screen(state: &mut State, world: &mut World, progress: f32) -> bool {
+///     false
+/// }
+///
+/// fn loaded(state: &mut State, world: &mut World, data: Vec<u8>) {}
+/// ```
+///
 /// Note: disables LSP for the affected token(s).
 #[proc_macro_attribute]
-pub fn init(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
-    let mainfun: TokenStream = include_str!("insert/main.rs").parse().unwrap();
+pub fn init(attr: TokenStream, mut item: TokenStream) -> TokenStream {
+    let mut on_event = "None";
+    let mut post_render = "None";
+    let mut loading = "None";
+    for arg in attr.to_string().split(',').map(str::trim) {
+        match arg {
+            "" => {}
+            "on_event" => on_event = "Some(on_event)",
+            "post_render" => post_render = "Some(post_render)",
+            "loading" => {
+                loading = "Some(genji::loading::Loading::new(load, loading_screen, loaded))"
+            }
+            _ => {
+                return r#"compile_error!("expected `on_event`, `post_render`, `loading`, or nothing");"#
+                    .parse()
+                    .unwrap()
+            }
+        }
+    }
+
+    let mainfun: TokenStream = include_str!("insert/main.rs")
+        .replace("ON_EVENT", on_event)
+        .replace("POST_RENDER", post_render)
+        .replace("LOADING", loading)
+        .parse()
+        .unwrap();
 
     item.extend(mainfun);
     item
@@ -6,6 +6,15 @@
 //! to conveniently attach audio to an entity, or otherwise manage
 //! it.
 //!
+//! To play a sound when an entity is spawned or despawned,
+//! give it a [`PlayOnSpawn`] or [`PlayOnDespawn`].
+//!
+//! Volume, speed, panning, and looping can be set with
+//! [`AudioSettings`], instead of kira's full settings.
+//!
+//! Several [`Music`] tracks can play at once, and
+//! [`Audio::crossfade`] fades from one to another.
+//!
 //! Genji uses [`kira`] for managing audio, and re-exports the
 //! crate for convenience.
 //!
@@ -34,28 +43,49 @@
 //! # }
 //! ```
 
-use std::{fmt::Debug, io::Cursor, path::Path};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    io::{Cursor, Read},
+    path::Path,
+    time::Duration,
+};
 
 use kira::{
     manager::{AudioManager, AudioManagerSettings},
     sound::{
         streaming::{StreamingSoundData, StreamingSoundHandle},
-        FromFileError, SoundData,
+        FromFileError, Region, SoundData,
     },
+    tween::Tween,
 };
 
 pub use kira::{
     self,
     sound::{
-        static_sound::{StaticSoundData as Sound, StaticSoundSettings as SoundSettings},
+        static_sound::{
+            StaticSoundData as Sound, StaticSoundHandle as SoundHandle,
+            StaticSoundSettings as SoundSettings,
+        },
         streaming::StreamingSoundSettings as MusicSettings,
+        PlaybackState as SoundState,
     },
 };
 
-use crate::store::Store;
+use crate::{
+    ecs::{Entity, NoSuchEntity, World},
+    helpers,
+    store::Store,
+};
 
 pub type Music = StreamingSoundData<FromFileError>;
-pub type MusicHandle = StreamingSoundHandle<FromFileError>;
+/// kira's handle to a playing [`Music`], as returned by [`Audio::play`].
+pub type MusicPlayback = StreamingSoundHandle<FromFileError>;
+
+/// Refers to a [`Music`] track that [`Audio`] keeps track of,
+/// from [`Audio::play_music`]. Cheap to copy and store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MusicHandle(u64);
 
 /// A way to store and access
 /// [`Sound`]s
@@ -67,6 +97,273 @@ pub type SoundStore = Store<Sound>;
 /// via human-friendly names.
 pub type MusicStore = Store<Music>;
 
+/// The common settings for [`Sound`]s and [`Music`], without
+/// the rest of kira's API. Converts into [`SoundSettings`] and
+/// [`MusicSettings`] (and can be passed wherever they're taken).
+///
+/// ```ignore
+/// # use genji::{prelude::*, audio::AudioSettings};
+/// # fn dummy(audio: &mut Audio, data: &'static [u8]) {
+/// let theme = Audio::music(data, AudioSettings::new().volume(0.5).looping()).unwrap();
+/// audio.play(theme);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioSettings {
+    /// The volume, as an amplitude. Defaults to `1.0`.
+    pub volume: f64,
+    /// How fast (and so how high-pitched) the audio
+    /// plays. Defaults to `1.0`.
+    pub speed: f64,
+    /// The panning, from `0.0` (left) to `1.0` (right).
+    /// Defaults to `0.5` (centered).
+    pub panning: f64,
+    /// The portion of the audio to loop, in seconds.
+    /// Defaults to None (no looping).
+    pub loop_region: Option<Region>,
+}
+
+impl AudioSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the volume, as an amplitude (`1.0` is unchanged).
+    pub fn volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Sets the playback speed (`1.0` is unchanged).
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets the panning, from `0.0` (left) to `1.0` (right).
+    pub fn panning(mut self, panning: f64) -> Self {
+        self.panning = panning;
+        self
+    }
+
+    /// Loops the whole audio.
+    pub fn looping(self) -> Self {
+        self.loop_region(..)
+    }
+
+    /// Loops part of the audio, in seconds, e.g. `2.5..` to
+    /// skip an intro when looping.
+    pub fn loop_region(mut self, region: impl Into<Region>) -> Self {
+        self.loop_region = Some(region.into());
+        self
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            speed: 1.0,
+            panning: 0.5,
+            loop_region: None,
+        }
+    }
+}
+
+impl From<AudioSettings> for SoundSettings {
+    fn from(settings: AudioSettings) -> Self {
+        SoundSettings::new()
+            .volume(settings.volume)
+            .playback_rate(settings.speed)
+            .panning(settings.panning)
+            .loop_region(settings.loop_region)
+    }
+}
+
+impl From<AudioSettings> for MusicSettings {
+    fn from(settings: AudioSettings) -> Self {
+        MusicSettings::new()
+            .volume(settings.volume)
+            .playback_rate(settings.speed)
+            .panning(settings.panning)
+            .loop_region(settings.loop_region)
+    }
+}
+
+/// Plays a sound once, when the entity is spawned (or when
+/// the component is added). The component is then removed.
+///
+/// ```ignore
+/// # use genji::{prelude::*, audio::PlayOnSpawn};
+/// # fn dummy(world: &mut World, shot: Sound) {
+/// world.spawn((shape::circle(2), Point(0, 0), PlayOnSpawn(shot)));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct PlayOnSpawn(pub Sound);
+
+/// Plays a sound when the entity is despawned.
+///
+/// Genji notices despawned entities after `onloop`, so it
+/// misses entities despawned in the same frame they were
+/// spawned in. Use [`WorldExt::despawn_playing`] to play the
+/// sound right away, whenever the entity was spawned.
+///
+/// ```ignore
+/// # use genji::{prelude::*, audio::PlayOnDespawn};
+/// # fn dummy(world: &mut World, audio: &mut Audio, explosion: Sound) {
+/// let enemy = world.spawn((Point(0, 0), PlayOnDespawn(explosion)));
+///
+/// // Later, the explosion plays.
+/// world.despawn_playing(enemy, audio).unwrap();
+/// # }
+/// ```
+///
+/// [`WorldExt::despawn_playing`]: crate::ecs::WorldExt::despawn_playing
+#[derive(Clone)]
+pub struct PlayOnDespawn(pub Sound);
+
+/// Plays [`PlayOnSpawn`] and [`PlayOnDespawn`] sounds.
+/// Updated by genji every frame, after `onloop`.
+#[derive(Default)]
+pub(crate) struct SoundTriggers {
+    /// Every entity with a `PlayOnDespawn`, as of the
+    /// last update, since its sound is gone by the time
+    /// it's despawned.
+    despawn: HashMap<Entity, Sound>,
+}
+
+impl SoundTriggers {
+    pub(crate) fn update(&mut self, world: &mut World, mut play: impl FnMut(Sound)) {
+        let spawned: Vec<(Entity, Sound)> = world
+            .query_mut::<&PlayOnSpawn>()
+            .into_iter()
+            .map(|(id, sound)| (id, sound.0.clone()))
+            .collect();
+        for (id, sound) in spawned {
+            play(sound);
+            let _ = world.remove_one::<PlayOnSpawn>(id);
+        }
+
+        // Entities that lost their `PlayOnDespawn` are forgotten
+        // quietly; the ones that are gone were despawned.
+        self.despawn
+            .retain(|&id, sound| match world.satisfies::<&PlayOnDespawn>(id) {
+                Ok(has_sound) => has_sound,
+                Err(NoSuchEntity) => {
+                    play(sound.clone());
+                    false
+                }
+            });
+
+        for (id, sound) in world.query_mut::<&PlayOnDespawn>() {
+            self.despawn.insert(id, sound.0.clone());
+        }
+    }
+
+    /// Despawns `id`, playing its `PlayOnDespawn` sound now,
+    /// even if it was spawned after the last update.
+    pub(crate) fn despawn(
+        &mut self,
+        world: &mut World,
+        id: Entity,
+        mut play: impl FnMut(Sound),
+    ) -> Result<(), NoSuchEntity> {
+        let sound = world.get::<&PlayOnDespawn>(id).ok().map(|s| s.0.clone());
+        world.despawn(id)?;
+
+        self.despawn.remove(&id);
+        if let Some(sound) = sound {
+            play(sound);
+        }
+
+        Ok(())
+    }
+}
+
+/// Conveniences for the handles returned by [`Audio::play`].
+pub trait PlaybackExt {
+    /// Returns whether or not the audio has finished playing
+    /// (or was stopped), and can't be resumed.
+    fn finished(&self) -> bool;
+}
+
+impl PlaybackExt for SoundHandle {
+    fn finished(&self) -> bool {
+        self.state() == SoundState::Stopped
+    }
+}
+
+impl PlaybackExt for MusicPlayback {
+    fn finished(&self) -> bool {
+        self.state() == SoundState::Stopped
+    }
+}
+
+/// A queue of sounds played one after another, e.g. lines
+/// of dialogue. Created by [`Audio::play_sequence`].
+///
+/// Sequences are polled: call [`SoundSequence::update`]
+/// every frame (e.g. in `onloop`) to start the next sound
+/// once the current one finishes.
+///
+/// ```ignore
+/// # use genji::prelude::*;
+/// // In init...
+/// # fn dummy1(audio: &mut Audio, lines: Vec<Sound>) {
+/// let mut dialogue = audio.play_sequence(lines);
+/// # }
+///
+/// // In onloop...
+/// # fn dummy2(audio: &mut Audio, dialogue: &mut genji::audio::SoundSequence) {
+/// if !dialogue.update(audio) {
+///     // The dialogue is over.
+/// }
+/// # }
+/// ```
+pub struct SoundSequence {
+    queue: VecDeque<Sound>,
+    current: Option<SoundHandle>,
+}
+
+impl SoundSequence {
+    /// Starts the next sound if the current one has finished.
+    /// Returns whether or not the sequence is still playing.
+    pub fn update(&mut self, audio: &mut Audio) -> bool {
+        while self.current.as_ref().is_none_or(PlaybackExt::finished) {
+            let Some(next) = self.queue.pop_front() else {
+                self.current = None;
+                return false;
+            };
+
+            self.current = audio.play(next);
+        }
+
+        true
+    }
+
+    /// Returns whether or not every sound has finished.
+    pub fn finished(&self) -> bool {
+        self.queue.is_empty() && self.current.as_ref().is_none_or(PlaybackExt::finished)
+    }
+
+    /// Returns the handle of the sound currently playing, if any.
+    pub fn current(&self) -> Option<&SoundHandle> {
+        self.current.as_ref()
+    }
+
+    /// Returns how many sounds are left after the current one.
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Drops every sound that hasn't started yet. The current
+    /// sound keeps playing; stop it through [`current`](Self::current).
+    pub fn skip_rest(&mut self) {
+        self.queue.clear();
+    }
+}
+
 /// The interface for creating and playing audio.
 ///
 /// ```ignore
@@ -93,48 +390,370 @@ pub type MusicStore = Store<Music>;
 /// }
 /// # }
 /// ```
-pub struct Audio(AudioManager);
+///
+/// If there's no audio device (e.g. on a headless machine),
+/// genji warns once and carries on; playing audio then
+/// does nothing.
+pub struct Audio {
+    manager: Option<AudioManager>,
+    triggers: SoundTriggers,
+    /// The music started by `play_music` that's still playing.
+    music: HashMap<MusicHandle, MusicPlayback>,
+    next_music: u64,
+}
 
 impl Audio {
     pub fn new() -> Self {
-        Self(
-            AudioManager::new(AudioManagerSettings::default()).expect("failed to initialize audio"),
-        )
+        match AudioManager::new(AudioManagerSettings::default()) {
+            Ok(manager) => Self::with_manager(Some(manager)),
+            Err(e) => {
+                helpers::report_error(&format!(
+                    "failed to initialize audio, continuing without sound: {e}"
+                ));
+                Self::with_manager(None)
+            }
+        }
     }
 
-    /// Plays a [`Sound`] or [`Music`]
-    pub fn play<S: SoundData>(&mut self, sound: S)
+    fn with_manager(manager: Option<AudioManager>) -> Self {
+        Self {
+            manager,
+            triggers: SoundTriggers::default(),
+            music: HashMap::new(),
+            next_music: 0,
+        }
+    }
+
+    /// Returns whether or not audio could be initialized.
+    /// If not, [`Audio::play`] does nothing.
+    pub fn is_available(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Plays a [`Sound`] or [`Music`], returning a handle
+    /// to control it or check when it's [finished](PlaybackExt::finished).
+    ///
+    /// Returns None if it couldn't be played (e.g. without
+    /// an audio device).
+    pub fn play<S: SoundData>(&mut self, sound: S) -> Option<S::Handle>
     where
         <S as SoundData>::Error: Debug,
     {
-        if let Err(e) = self.0.play(sound) {
-            eprintln!("failed to play sound: {e:?}");
+        play_on(&mut self.manager, sound)
+    }
+
+    /// Plays the [`PlayOnSpawn`] and [`PlayOnDespawn`] sounds
+    /// due since the last update, and forgets finished music.
+    pub(crate) fn update(&mut self, world: &mut World) {
+        let manager = &mut self.manager;
+        self.triggers.update(world, |sound| {
+            play_on(manager, sound);
+        });
+
+        self.music.retain(|_, playback| !playback.finished());
+    }
+
+    /// Despawns an entity, playing its [`PlayOnDespawn`] sound
+    /// right away. See [`WorldExt::despawn_playing`](crate::ecs::WorldExt::despawn_playing).
+    pub(crate) fn despawn(&mut self, world: &mut World, id: Entity) -> Result<(), NoSuchEntity> {
+        let manager = &mut self.manager;
+        self.triggers.despawn(world, id, |sound| {
+            play_on(manager, sound);
+        })
+    }
+
+    /// Plays a [`Sound`] with different settings, without
+    /// changing the sound itself. The audio data is shared,
+    /// not copied.
+    pub fn play_with(
+        &mut self,
+        sound: &Sound,
+        settings: impl Into<SoundSettings>,
+    ) -> Option<SoundHandle> {
+        self.play(sound.with_settings(settings.into()))
+    }
+
+    /// Plays a [`Music`] track, keeping track of it until it
+    /// finishes. Any number of tracks can play at once, e.g.
+    /// layers of an adaptive soundtrack; use the handle to
+    /// [`crossfade`](Audio::crossfade) between them, or to
+    /// [`stop`](Audio::stop_music) or [adjust](Audio::music_playback) them.
+    ///
+    /// Returns None if it couldn't be played.
+    pub fn play_music(&mut self, music: Music) -> Option<MusicHandle> {
+        let playback = play_on(&mut self.manager, music)?;
+
+        let handle = MusicHandle(self.next_music);
+        self.next_music += 1;
+        self.music.insert(handle, playback);
+
+        Some(handle)
+    }
+
+    /// Fades `from` out while fading `to` in, both over
+    /// `duration_ms` milliseconds, e.g. when combat starts.
+    /// `from` stops once it's silent (if it's still playing).
+    /// Returns the handle of `to`, or None if it couldn't be played.
+    ///
+    /// ```ignore
+    /// # use genji::prelude::*;
+    /// # fn dummy(audio: &mut Audio, explore: Music, combat: Music) -> Option<()> {
+    /// let mut current = audio.play_music(explore)?;
+    ///
+    /// // When an enemy spots the player...
+    /// current = audio.crossfade(current, combat, 2000)?;
+    /// # None
+    /// # }
+    /// ```
+    pub fn crossfade(
+        &mut self,
+        from: MusicHandle,
+        mut to: Music,
+        duration_ms: u64,
+    ) -> Option<MusicHandle> {
+        let tween = fade(duration_ms);
+        self.stop_music(from, duration_ms);
+
+        to.settings.fade_in_tween = Some(tween);
+        self.play_music(to)
+    }
+
+    /// Fades a track out over `duration_ms` milliseconds
+    /// (`0` stops it right away), and forgets it. Does
+    /// nothing if it already finished.
+    pub fn stop_music(&mut self, handle: MusicHandle, duration_ms: u64) {
+        let Some(mut playback) = self.music.remove(&handle) else {
+            return;
+        };
+
+        if let Err(e) = playback.stop(fade(duration_ms)) {
+            helpers::report_error(&format!("failed to stop music: {e:?}"));
         }
     }
 
+    /// Returns whether or not a track is still playing.
+    pub fn is_playing(&self, handle: MusicHandle) -> bool {
+        self.music.contains_key(&handle)
+    }
+
+    /// Gives access to kira's handle for a track, e.g. to pause
+    /// it or change its volume. Returns None once it's finished.
+    pub fn music_playback(&mut self, handle: MusicHandle) -> Option<&mut MusicPlayback> {
+        self.music.get_mut(&handle)
+    }
+
+    /// Plays `sounds` one after another. See [`SoundSequence`].
+    pub fn play_sequence<I: IntoIterator<Item = Sound>>(&mut self, sounds: I) -> SoundSequence {
+        let mut sequence = SoundSequence {
+            queue: sounds.into_iter().collect(),
+            current: None,
+        };
+
+        sequence.update(self);
+        sequence
+    }
+
     /// Creates [`Sound`] (short-lived audio) from static data.
-    pub fn sound(data: &'static [u8], settings: SoundSettings) -> Option<Sound> {
-        Sound::from_cursor(Cursor::new(data), settings).ok()
+    pub fn sound(data: &'static [u8], settings: impl Into<SoundSettings>) -> Option<Sound> {
+        Sound::from_cursor(Cursor::new(data), settings.into()).ok()
+    }
+
+    /// Creates [`Sound`] (short-lived audio) from owned data,
+    /// such as audio loaded at runtime.
+    pub fn sound_from_vec(data: Vec<u8>, settings: impl Into<SoundSettings>) -> Option<Sound> {
+        Sound::from_cursor(Cursor::new(data), settings.into()).ok()
+    }
+
+    /// Creates [`Sound`] (short-lived audio) from a reader,
+    /// such as a file inside of an archive.
+    ///
+    /// The reader is read to the end up-front.
+    pub fn sound_from_reader<R: Read>(
+        mut reader: R,
+        settings: impl Into<SoundSettings>,
+    ) -> Option<Sound> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).ok()?;
+        Self::sound_from_vec(data, settings)
     }
 
     /// Creates [`Sound`] (short-lived audio) from a file.
-    pub fn sound_from_file<P: AsRef<Path>>(path: P, settings: SoundSettings) -> Option<Sound> {
-        Sound::from_file(path, settings).ok()
+    pub fn sound_from_file<P: AsRef<Path>>(
+        path: P,
+        settings: impl Into<SoundSettings>,
+    ) -> Option<Sound> {
+        Sound::from_file(path, settings.into()).ok()
     }
 
     /// Creates [`Music`] (streamable audio) from static data.
-    pub fn music(data: &'static [u8], settings: MusicSettings) -> Option<Music> {
-        Music::from_cursor(Cursor::new(data), settings).ok()
+    pub fn music(data: &'static [u8], settings: impl Into<MusicSettings>) -> Option<Music> {
+        Music::from_cursor(Cursor::new(data), settings.into()).ok()
+    }
+
+    /// Creates [`Music`] (streamable audio) from owned data,
+    /// such as audio loaded at runtime. The data lives as
+    /// long as the music does.
+    pub fn music_from_vec(data: Vec<u8>, settings: impl Into<MusicSettings>) -> Option<Music> {
+        Music::from_cursor(Cursor::new(data), settings.into()).ok()
+    }
+
+    /// Creates [`Music`] (streamable audio) from a reader,
+    /// such as a file inside of an archive.
+    ///
+    /// The reader is read to the end up-front, so only
+    /// the decoding is streamed.
+    pub fn music_from_reader<R: Read>(
+        mut reader: R,
+        settings: impl Into<MusicSettings>,
+    ) -> Option<Music> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).ok()?;
+        Self::music_from_vec(data, settings)
     }
 
     /// Creates [`Music`] (streamable audio) from a file.
-    pub fn music_from_file<P: AsRef<Path>>(path: P, settings: MusicSettings) -> Option<Music> {
-        Music::from_file(path, settings).ok()
+    pub fn music_from_file<P: AsRef<Path>>(
+        path: P,
+        settings: impl Into<MusicSettings>,
+    ) -> Option<Music> {
+        Music::from_file(path, settings.into()).ok()
     }
 }
 
+/// A linear fade over `duration_ms` milliseconds.
+fn fade(duration_ms: u64) -> Tween {
+    Tween {
+        duration: Duration::from_millis(duration_ms),
+        ..Default::default()
+    }
+}
+
+/// Plays audio through `manager`, if there is one.
+fn play_on<S: SoundData>(manager: &mut Option<AudioManager>, sound: S) -> Option<S::Handle>
+where
+    <S as SoundData>::Error: Debug,
+{
+    manager
+        .as_mut()?
+        .play(sound)
+        .map_err(|e| helpers::report_error(&format!("failed to play sound: {e:?}")))
+        .ok()
+}
+
 impl Default for Audio {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::ecs::World;
+
+    use super::{
+        Audio, MusicHandle, MusicSettings, PlayOnDespawn, PlayOnSpawn, Sound, SoundSettings,
+        SoundTriggers,
+    };
+
+    /// A silent sound, told apart from others by its sample rate.
+    fn sound(id: u32) -> Sound {
+        Sound {
+            sample_rate: id,
+            frames: Arc::new([]),
+            settings: SoundSettings::default(),
+        }
+    }
+
+    /// Updates `triggers`, returning the ids of the sounds played.
+    fn update(triggers: &mut SoundTriggers, world: &mut World) -> Vec<u32> {
+        let mut played = Vec::new();
+        triggers.update(world, |sound| played.push(sound.sample_rate));
+        played
+    }
+
+    #[test]
+    fn play_on_spawn_plays_once() {
+        let mut world = World::new();
+        let mut triggers = SoundTriggers::default();
+        let id = world.spawn((PlayOnSpawn(sound(1)),));
+
+        assert_eq!(update(&mut triggers, &mut world), [1]);
+        assert!(world.get::<&PlayOnSpawn>(id).is_err());
+        assert_eq!(update(&mut triggers, &mut world), []);
+    }
+
+    #[test]
+    fn play_on_despawn_plays_after_despawning() {
+        let mut world = World::new();
+        let mut triggers = SoundTriggers::default();
+        let id = world.spawn((PlayOnDespawn(sound(2)),));
+        let quiet = world.spawn((PlayOnDespawn(sound(3)),));
+
+        assert_eq!(update(&mut triggers, &mut world), []);
+
+        // Removing the component disarms it.
+        world.remove_one::<PlayOnDespawn>(quiet).unwrap();
+        assert_eq!(update(&mut triggers, &mut world), []);
+        world.despawn(quiet).unwrap();
+        world.despawn(id).unwrap();
+        assert_eq!(update(&mut triggers, &mut world), [2]);
+        assert_eq!(update(&mut triggers, &mut world), []);
+    }
+
+    #[test]
+    fn despawn_plays_in_the_same_frame() {
+        let mut world = World::new();
+        let mut triggers = SoundTriggers::default();
+
+        // Despawned before genji ever saw it.
+        let fresh = world.spawn((PlayOnDespawn(sound(4)),));
+        let mut played = Vec::new();
+        triggers
+            .despawn(&mut world, fresh, |sound| played.push(sound.sample_rate))
+            .unwrap();
+        assert_eq!(played, [4]);
+
+        // Already tracked; shouldn't play a second time.
+        let tracked = world.spawn((PlayOnDespawn(sound(5)),));
+        update(&mut triggers, &mut world);
+        triggers.despawn(&mut world, tracked, |_| {}).unwrap();
+        assert_eq!(update(&mut triggers, &mut world), []);
+
+        assert!(triggers.despawn(&mut world, tracked, |_| {}).is_err());
+    }
+
+    /// A short, silent WAV file.
+    fn wav() -> Vec<u8> {
+        let samples = [0u8; 64];
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&samples);
+        wav
+    }
+
+    #[test]
+    fn music_without_audio_is_a_no_op() {
+        let mut audio = Audio::with_manager(None);
+        let music = || Audio::music_from_vec(wav(), MusicSettings::default()).unwrap();
+
+        assert_eq!(audio.play_music(music()), None);
+        assert_eq!(audio.crossfade(MusicHandle(0), music(), 500), None);
+
+        audio.stop_music(MusicHandle(0), 0);
+        assert!(!audio.is_playing(MusicHandle(0)));
+        assert!(audio.music_playback(MusicHandle(0)).is_none());
+    }
+}
@@ -3,13 +3,549 @@
 //! Genji uses the excellent [hecs]
 //! ECS crate, with no real modifications. However, an
 //! [`EntityStore`] struct is provided
-//! to give convenient ID's to entities.
+//! to give convenient ID's to entities, and [`WorldExt`]
+//! adds some conveniences to the [`World`].
+//!
+//! Iteration order in hecs depends on how entities are
+//! stored, not when they were spawned, and can change as
+//! components are added and removed. Where that matters,
+//! give entities an [`Order`] and use
+//! [`WorldExt::query_ordered`].
+//!
+//! To reset the world (e.g. between levels), use
+//! [`World::clear`], or [`WorldExt::clear_matching`]
+//! to spare entities marked with [`Keep`].
+//!
+//! With the `serde` feature, worlds can be saved to and
+//! loaded from files via [`save_world`] and [`load_world`].
 
 pub use hecs::*;
 
-use crate::store::Store;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    audio::Audio,
+    graphics::{
+        resolve_transform,
+        sprite::{IntoSprite, Texture},
+        AlwaysOnTop, Angle, Depth, Parent,
+    },
+    shape::{Circle, Contains, FPoint, Point, Rect, Triangle},
+    store::Store,
+};
+
+#[cfg(feature = "serde")]
+use crate::graphics::{Color, Fill, Stroke, StrokeCap, StrokeJoin, StrokeWeight};
 
 /// A way to store and access
 /// [`Entity`]s
 /// via human-friendly names.
 pub type EntityStore = Store<Entity>;
+
+/// Gives an entity a fixed place in iteration order, for
+/// [`WorldExt::query_ordered`] and for drawing sprites that
+/// share a [`Depth`](crate::graphics::Depth) (lower first).
+///
+/// ```
+/// # use genji::prelude::*;
+/// let mut world = World::new();
+///
+/// for (i, name) in ["new game", "load", "quit"].into_iter().enumerate() {
+///     world.spawn((name, Order(i as u64)));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Order(pub u64);
+
+impl Deref for Order {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Order {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Marks an entity to survive [`WorldExt::clear_matching`],
+/// e.g. persistent UI across a level reset.
+///
+/// ```
+/// # use genji::{ecs::Keep, prelude::*};
+/// let mut world = World::new();
+/// world.spawn(("score", Keep));
+/// world.spawn(("enemy",));
+///
+/// world.clear_matching::<()>();
+/// assert_eq!(world.len(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keep;
+
+/// Extra conveniences for the [`World`].
+pub trait WorldExt {
+    /// Despawns every entity matching `Q` for which `f` returns true,
+    /// returning how many were despawned.
+    ///
+    /// This saves you from collecting the ids yourself, since
+    /// despawning while iterating a query isn't allowed.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// struct Hp(pub u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Hp(0),));
+    /// world.spawn((Hp(20),));
+    ///
+    /// assert_eq!(world.despawn_where::<&Hp>(|hp| hp.0 == 0), 1);
+    /// ```
+    fn despawn_where<Q: Query>(&mut self, f: impl FnMut(Q::Item<'_>) -> bool) -> usize;
+
+    /// Runs a query, returning the results sorted by [`Order`].
+    /// Entities without an `Order` come first (like when drawing),
+    /// and ties are broken by entity id, so the result is the
+    /// same every time. `Q` may borrow `Order` itself, even mutably.
+    ///
+    /// Unlike a plain query, this collects and sorts every
+    /// result (`O(n log n)`), so prefer [`World::query_mut`]
+    /// when the order doesn't matter.
+    ///
+    /// ```
+    /// # use genji::{ecs::Order, prelude::*};
+    /// let mut world = World::new();
+    /// world.spawn(("second", Order(2)));
+    /// world.spawn(("first", Order(1)));
+    ///
+    /// let names: Vec<_> = world
+    ///     .query_ordered::<&&str>()
+    ///     .into_iter()
+    ///     .map(|(_, name)| *name)
+    ///     .collect();
+    ///
+    /// assert_eq!(names, ["first", "second"]);
+    /// ```
+    fn query_ordered<Q: Query>(&mut self) -> Vec<(Entity, Q::Item<'_>)>;
+
+    /// Despawns every entity matching `Q`, except those with
+    /// a [`Keep`], returning how many were despawned. Use
+    /// `clear_matching::<()>()` to clear every entity that
+    /// isn't kept, e.g. when changing levels.
+    ///
+    /// To despawn *everything*, kept or not, use [`World::clear`].
+    ///
+    /// ```
+    /// # use genji::{ecs::Keep, prelude::*};
+    /// struct Enemy;
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Enemy,));
+    /// world.spawn((Enemy, Keep));
+    /// world.spawn(("player",));
+    ///
+    /// assert_eq!(world.clear_matching::<&Enemy>(), 1);
+    /// assert_eq!(world.len(), 2);
+    /// ```
+    fn clear_matching<Q: Query>(&mut self) -> usize;
+
+    /// Despawns an entity, playing its [`PlayOnDespawn`] sound
+    /// right away. Plain [`World::despawn`] plays it too, but
+    /// only after `onloop`, and not at all for entities spawned
+    /// in the same frame.
+    ///
+    /// ```ignore
+    /// # use genji::{prelude::*, audio::PlayOnDespawn};
+    /// # fn dummy(world: &mut World, audio: &mut Audio, pop: Sound) {
+    /// let bubble = world.spawn((Point(0, 0), PlayOnDespawn(pop)));
+    /// world.despawn_playing(bubble, audio).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`PlayOnDespawn`]: crate::audio::PlayOnDespawn
+    fn despawn_playing(&mut self, id: Entity, audio: &mut Audio) -> Result<(), NoSuchEntity>;
+
+    /// Spawns anything that implements [`IntoSprite`], e.g.
+    /// your own entity types, or a
+    /// [`SpriteBundle`](crate::graphics::sprite::SpriteBundle).
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// let mut world = World::new();
+    /// let id = world.spawn_sprite(shape::rect(20, 20).at(0, 0));
+    /// assert!(world.get::<&Point>(id).is_ok());
+    /// ```
+    fn spawn_sprite(&mut self, sprite: impl IntoSprite) -> Entity;
+
+    /// Returns every sprite under `point`, frontmost first, e.g.
+    /// for click-to-select. Uses each sprite's [`Contains`], with
+    /// its position (or [`FPoint`], rounded), [`Angle`] and
+    /// [`Parent`]s, just like it's drawn.
+    ///
+    /// Sprites are ordered the way they're drawn: [`AlwaysOnTop`]
+    /// first, then by [`Depth`] and [`Order`]. Any remaining ties go
+    /// to the higher entity id (usually the newer entity). Hidden
+    /// sprites (`Depth(0)`, unless they're `AlwaysOnTop`) and text
+    /// are never picked.
+    ///
+    /// `point` is in world coordinates, like [`Point`], so undo any
+    /// camera first when picking with the mouse.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// let mut world = World::new();
+    /// let back = world.spawn((shape::rect(20, 20), Point(0, 0), Depth(2)));
+    /// let front = world.spawn((shape::circle(5), Point(3, 0)));
+    ///
+    /// assert_eq!(world.pick(Point(4, 1)), [front, back]);
+    /// assert_eq!(world.pick(Point(-8, -8)), [back]);
+    /// ```
+    ///
+    /// [`Contains`]: crate::shape::Contains
+    fn pick(&self, point: Point) -> Vec<Entity>;
+}
+
+impl WorldExt for World {
+    fn despawn_where<Q: Query>(&mut self, mut f: impl FnMut(Q::Item<'_>) -> bool) -> usize {
+        let ids: Vec<Entity> = self
+            .query_mut::<Q>()
+            .into_iter()
+            .filter_map(|(id, item)| f(item).then_some(id))
+            .collect();
+
+        for &id in &ids {
+            let _ = self.despawn(id);
+        }
+
+        ids.len()
+    }
+
+    fn query_ordered<Q: Query>(&mut self) -> Vec<(Entity, Q::Item<'_>)> {
+        // Orders are read first, so that `Q` can borrow `Order` mutably.
+        let orders: HashMap<Entity, Order> = self
+            .query_mut::<&Order>()
+            .into_iter()
+            .map(|(id, order)| (id, *order))
+            .collect();
+
+        let mut items: Vec<_> = self
+            .query_mut::<Q>()
+            .into_iter()
+            .map(|(id, item)| ((orders.get(&id).copied(), id.id()), id, item))
+            .collect();
+
+        items.sort_unstable_by_key(|(key, _, _)| *key);
+        items.into_iter().map(|(_, id, item)| (id, item)).collect()
+    }
+
+    fn clear_matching<Q: Query>(&mut self) -> usize {
+        self.despawn_where::<Without<Q, &Keep>>(|_| true)
+    }
+
+    fn despawn_playing(&mut self, id: Entity, audio: &mut Audio) -> Result<(), NoSuchEntity> {
+        audio.despawn(self, id)
+    }
+
+    fn spawn_sprite(&mut self, sprite: impl IntoSprite) -> Entity {
+        self.spawn(sprite.into_sprite())
+    }
+
+    fn pick(&self, point: Point) -> Vec<Entity> {
+        // Sorted so that the frontmost sprite has the largest key.
+        let mut hits = Vec::new();
+
+        macro_rules! pick {
+            ( $( $sprite_type:ident ),* ) => {$(
+                let mut query = self.query::<(
+                    &$sprite_type,
+                    Option<&Point>,
+                    Option<&FPoint>,
+                    Option<&Angle>,
+                    Option<&Depth>,
+                    Option<&Order>,
+                    Option<&AlwaysOnTop>,
+                    Option<&Parent>,
+                )>();
+                for (id, (sprite, pos, fpos, angle, depth, order, on_top, parent)) in query.iter() {
+                    let Some(pos) = fpos.map(FPoint::round).or(pos.copied()) else {
+                        continue;
+                    };
+
+                    let depth = depth.map_or(1, |depth| **depth);
+                    if depth == 0 && on_top.is_none() {
+                        continue;
+                    }
+
+                    let angle = angle.map_or(0.0, |angle| **angle);
+                    let (pos, angle) = match parent {
+                        Some(_) => resolve_transform(self, id, pos, angle),
+                        None => (pos, angle),
+                    };
+
+                    if sprite.contains(pos, point, angle) {
+                        let order = order.copied();
+                        let key = match on_top {
+                            Some(_) => (true, Reverse(0), order, id.id()),
+                            None => (false, Reverse(depth), order, id.id()),
+                        };
+                        hits.push((key, id));
+                    }
+                }
+            )*};
+        }
+
+        pick!(Rect, Circle, Triangle, Texture);
+
+        hits.sort_unstable_by_key(|(key, _)| Reverse(*key));
+        hits.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+/// Serializes genji's built-in components (positions, shapes,
+/// and sprite data) of a single entity.
+///
+/// Text and textures aren't saved, since they hold fonts and
+/// image data; respawn those yourself after loading.
+///
+/// To save your own components as well, write a
+/// [`SerializeContext`](serialize::row::SerializeContext) that
+/// calls this before adding its own entries:
+///
+/// ```
+/// # use genji::ecs::{self, serialize::row::*, EntityRef};
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Hp(u32);
+///
+/// struct MyContext;
+///
+/// impl SerializeContext for MyContext {
+///     fn serialize_entity<S>(&mut self, entity: EntityRef<'_>, mut map: S) -> Result<S::Ok, S::Error>
+///     where
+///         S: serde::ser::SerializeMap,
+///     {
+///         ecs::serialize_builtin(&entity, &mut map)?;
+///         try_serialize::<Hp, _, _>(&entity, "Hp", &mut map)?;
+///         map.end()
+///     }
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn serialize_builtin<S: serde::ser::SerializeMap>(
+    entity: &EntityRef<'_>,
+    map: &mut S,
+) -> Result<(), S::Error> {
+    use serialize::row::try_serialize;
+
+    macro_rules! builtin {
+        ( $( $ty:ident ),* ) => {$(
+            try_serialize::<$ty, _, _>(entity, stringify!($ty), map)?;
+        )*};
+    }
+
+    builtin!(
+        Point,
+        FPoint,
+        Rect,
+        Circle,
+        Triangle,
+        Angle,
+        Color,
+        Depth,
+        Fill,
+        StrokeWeight,
+        StrokeJoin,
+        StrokeCap,
+        Stroke,
+        Parent,
+        AlwaysOnTop,
+        Order,
+        Keep
+    );
+
+    Ok(())
+}
+
+/// Deserializes one of genji's built-in components named `key`
+/// into `entity`. Returns `false` (without reading the value)
+/// if `key` isn't a built-in component, so that a custom
+/// [`DeserializeContext`](serialize::row::DeserializeContext)
+/// can handle it instead.
+///
+/// ```
+/// # use genji::ecs::{self, serialize::row::*, EntityBuilder};
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Hp(u32);
+///
+/// struct MyContext;
+///
+/// impl DeserializeContext for MyContext {
+///     fn deserialize_entity<'de, M>(&mut self, mut map: M, entity: &mut EntityBuilder) -> Result<(), M::Error>
+///     where
+///         M: serde::de::MapAccess<'de>,
+///     {
+///         while let Some(key) = map.next_key::<String>()? {
+///             if ecs::deserialize_builtin(&key, &mut map, entity)? {
+///                 continue;
+///             }
+///
+///             match key.as_str() {
+///                 "Hp" => {
+///                     entity.add(map.next_value::<Hp>()?);
+///                 }
+///                 _ => {
+///                     map.next_value::<serde::de::IgnoredAny>()?;
+///                 }
+///             }
+///         }
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn deserialize_builtin<'de, M: serde::de::MapAccess<'de>>(
+    key: &str,
+    map: &mut M,
+    entity: &mut EntityBuilder,
+) -> Result<bool, M::Error> {
+    macro_rules! builtin {
+        ( $( $ty:ident ),* ) => {
+            match key {
+                $( stringify!($ty) => {
+                    entity.add(map.next_value::<$ty>()?);
+                } )*
+                _ => return Ok(false),
+            }
+        };
+    }
+
+    builtin!(
+        Point,
+        FPoint,
+        Rect,
+        Circle,
+        Triangle,
+        Angle,
+        Color,
+        Depth,
+        Fill,
+        StrokeWeight,
+        StrokeJoin,
+        StrokeCap,
+        Stroke,
+        Parent,
+        AlwaysOnTop,
+        Order,
+        Keep
+    );
+
+    Ok(true)
+}
+
+/// A context that saves and loads only genji's built-in
+/// components. Used by [`save_world`] and [`load_world`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinComponents;
+
+#[cfg(feature = "serde")]
+impl serialize::row::SerializeContext for BuiltinComponents {
+    fn serialize_entity<S>(&mut self, entity: EntityRef<'_>, mut map: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::SerializeMap,
+    {
+        serialize_builtin(&entity, &mut map)?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serialize::row::DeserializeContext for BuiltinComponents {
+    fn deserialize_entity<'de, M>(
+        &mut self,
+        mut map: M,
+        entity: &mut EntityBuilder,
+    ) -> Result<(), M::Error>
+    where
+        M: serde::de::MapAccess<'de>,
+    {
+        while let Some(key) = map.next_key::<String>()? {
+            if !deserialize_builtin(&key, &mut map, entity)? {
+                map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Saves every entity in `world`, along with its built-in
+/// components, to a (RON) file. Entities without any built-in
+/// components are saved empty.
+///
+/// Use [`save_world_with`] to save your own components too.
+///
+/// ```
+/// # use genji::{prelude::*, ecs};
+/// let mut world = World::new();
+/// world.spawn((shape::rect(20, 20), Point(10, 5), Color::new(255, 0, 0, 255)));
+///
+/// ecs::save_world(&world, "save.ron").unwrap();
+/// let loaded = ecs::load_world("save.ron").unwrap();
+/// ```
+#[cfg(feature = "serde")]
+pub fn save_world(world: &World, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    save_world_with(world, path, &mut BuiltinComponents)
+}
+
+/// Saves every entity in `world` to a (RON) file, using
+/// `context` to decide which components are saved.
+#[cfg(feature = "serde")]
+pub fn save_world_with<C: serialize::row::SerializeContext>(
+    world: &World,
+    path: impl AsRef<std::path::Path>,
+    context: &mut C,
+) -> std::io::Result<()> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut ser = ron::Serializer::new(file, Some(ron::ser::PrettyConfig::default()))
+        .map_err(invalid_data)?;
+
+    serialize::row::serialize(world, context, &mut ser).map_err(invalid_data)
+}
+
+/// Loads a world saved with [`save_world`]. Entities keep the
+/// same ids they had when they were saved.
+#[cfg(feature = "serde")]
+pub fn load_world(path: impl AsRef<std::path::Path>) -> std::io::Result<World> {
+    load_world_with(path, &mut BuiltinComponents)
+}
+
+/// Loads a world saved with [`save_world_with`], using `context`
+/// to reconstruct the components.
+#[cfg(feature = "serde")]
+pub fn load_world_with<C: serialize::row::DeserializeContext>(
+    path: impl AsRef<std::path::Path>,
+    context: &mut C,
+) -> std::io::Result<World> {
+    let data = std::fs::read_to_string(path)?;
+    let mut de = ron::Deserializer::from_str(&data).map_err(invalid_data)?;
+
+    let world = serialize::row::deserialize(context, &mut de).map_err(invalid_data)?;
+    de.end().map_err(invalid_data)?;
+
+    Ok(world)
+}
+
+#[cfg(feature = "serde")]
+fn invalid_data(e: impl std::error::Error + Send + Sync + 'static) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,159 @@
+//! Utilities for loading and using texture atlases.
+//!
+//! Unlike a [`Spritemap`](super::spritemap::Spritemap), whose
+//! sprites lie on a uniform grid, an atlas holds arbitrarily
+//! placed and sized frames, each with a name. This matches
+//! the output of packing tools such as TexturePacker.
+//!
+//! With the `serde` feature, the frames can be loaded
+//! from a RON file mapping each name to its `(x, y, w, h)`:
+//!
+//! ```ron
+//! {
+//!     "player_walk_01": (0, 0, 32, 48),
+//!     "player_walk_02": (32, 0, 32, 48),
+//!     "coin": (64, 0, 16, 16),
+//! }
+//! ```
+//!
+//! Like a spritemap, frames share the atlas's image
+//! instead of copying it.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+
+use image::RgbaImage;
+
+use super::sprite::{self, ImageFormat};
+
+/// A region of an atlas, as `(x, y, w, h)` in pixels
+/// from the top-left of its image.
+pub type Frame = (u32, u32, u32, u32);
+
+/// A texture with named frames.
+///
+/// ```
+/// # use genji::graphics::{atlas::Atlas, sprite::ImageFormat};
+/// # fn dummy(data: Vec<u8>) -> Option<()> {
+/// let atlas = Atlas::new(
+///     data,
+///     ImageFormat::Png,
+///     [("player_walk_01", (0, 0, 32, 48)), ("coin", (32, 0, 16, 16))],
+/// )?;
+///
+/// let coin = atlas.get("coin", None, None)?;
+/// # Some(())
+/// # }
+/// ```
+pub struct Atlas {
+    tex: Arc<[u8]>,
+    dims: (u32, u32),
+
+    frames: HashMap<String, Frame>,
+}
+
+impl Atlas {
+    /// Creates a new atlas from image data and its frames.
+    ///
+    /// If the image can't be loaded, or any frame doesn't
+    /// fit inside of it, returns None.
+    pub fn new<D, S, I>(data: D, fmt: ImageFormat, frames: I) -> Option<Self>
+    where
+        D: Into<Vec<u8>>,
+        S: ToString,
+        I: IntoIterator<Item = (S, Frame)>,
+    {
+        let data = image::load(Cursor::new(data.into()), fmt).ok()?.to_rgba8();
+        Self::from_image(data, frames)
+    }
+
+    /// Creates a new atlas from an image file and its frames.
+    ///
+    /// The format is derived from the file's extension, falling
+    /// back to guessing from its contents.
+    ///
+    /// If the image can't be loaded, or any frame doesn't
+    /// fit inside of it, returns None.
+    pub fn from_file<P, S, I>(path: P, frames: I) -> Option<Self>
+    where
+        P: AsRef<Path>,
+        S: ToString,
+        I: IntoIterator<Item = (S, Frame)>,
+    {
+        let data = sprite::load_image_file(path.as_ref(), None)?;
+        Self::from_image(data, frames)
+    }
+
+    /// Creates a new atlas from an image file, and a RON
+    /// file describing its frames (see the [module docs](self)).
+    #[cfg(feature = "serde")]
+    pub fn load(
+        image_path: impl AsRef<Path>,
+        frames_path: impl AsRef<Path>,
+    ) -> std::io::Result<Self> {
+        let frames: HashMap<String, Frame> = ron::from_str(&std::fs::read_to_string(frames_path)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        Self::from_file(image_path, frames).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "failed to load atlas image, or a frame didn't fit in it",
+            )
+        })
+    }
+
+    fn from_image<S, I>(data: RgbaImage, frames: I) -> Option<Self>
+    where
+        S: ToString,
+        I: IntoIterator<Item = (S, Frame)>,
+    {
+        let mut atlas = Self {
+            dims: data.dimensions(),
+            tex: data.into_raw().into(),
+            frames: HashMap::new(),
+        };
+
+        for (name, frame) in frames {
+            if !atlas.insert(name, frame) {
+                return None;
+            }
+        }
+
+        Some(atlas)
+    }
+
+    /// Adds (or replaces) a frame. Returns false, without
+    /// adding it, if the frame doesn't fit inside the image.
+    pub fn insert<S: ToString>(&mut self, name: S, frame: Frame) -> bool {
+        let (x, y, w, h) = frame;
+        if x.checked_add(w).is_none_or(|right| right > self.dims.0)
+            || y.checked_add(h).is_none_or(|bottom| bottom > self.dims.1)
+        {
+            return false;
+        }
+
+        self.frames.insert(name.to_string(), frame);
+        true
+    }
+
+    /// Get a sprite showing the frame called `name`.
+    ///
+    /// `w` and `h` correspond to the `w` and `h` arguments on
+    /// [`sprite::texture`].
+    pub fn get(&self, name: &str, w: Option<i32>, h: Option<i32>) -> Option<sprite::Texture> {
+        let frame = *self.frames.get(name)?;
+        Some(sprite::texture_region(&self.tex, self.dims, frame, w, h))
+    }
+
+    /// Returns the region of the frame called `name`.
+    pub fn frame(&self, name: &str) -> Option<Frame> {
+        self.frames.get(name).copied()
+    }
+
+    /// Returns the names of every frame, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.frames.keys().map(String::as_str)
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! In genji, sprites are components that can optionally
 //! have other components attached to add information. The
-//! exception is [`Point`](crate::shape::Point),
-//! which must be specified or the sprite will not be drawn.
+//! exception is [`Point`](crate::shape::Point) (or
+//! [`FPoint`](crate::shape::FPoint), which takes precedence),
+//! which must be specified or the sprite will not be drawn
+//! (genji warns about this when `GameState.debug` is set).
 //! All the others have default values.
 //!
 //! The following sprites are available as components:
@@ -19,14 +21,117 @@
 //! [`Depth`],
 //! [`Fill`],
 //! [`Point`](crate::shape::Point),
-//! [`StrokeWeight`].
+//! [`StrokeWeight`],
+//! [`StrokeJoin`],
+//! [`StrokeCap`],
+//! [`Stroke`],
+//! [`AlwaysOnTop`].
+//!
+//! Sprites can also be attached to another entity via
+//! [`Parent`], making their position and angle relative
+//! to it.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+use ab_glyph::FontArc;
 
-use std::ops::{Deref, DerefMut};
+use crate::{
+    ecs::{Entity, Or, World},
+    helpers,
+    shape::{self, Circle, FPoint, Point, Rect, Triangle},
+    util::Camera,
+};
 
+use sprite::{Text, Texture};
+
+pub mod atlas;
 pub(crate) mod shaders;
 pub mod sprite;
 pub mod spritemap;
-mod text;
+pub mod text;
+
+/// What the graphics driver supports, e.g. for gating optional
+/// effects or for bug reports. Queried once, when the window
+/// opens; see `GameState::graphics_info`.
+///
+/// Formatting it with `{}` gives a short, multi-line summary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphicsInfo {
+    /// The full OpenGL version string, e.g.
+    /// `"3.3 (Core Profile) Mesa 23.0.4"`.
+    pub gl_version: String,
+    /// The newest supported GLSL version, as `(major, minor)`.
+    pub glsl_version: (u8, u8),
+    pub vendor: String,
+    /// The name of the GPU (or software renderer).
+    pub renderer: String,
+    /// The largest width or height of a texture, in pixels.
+    pub max_texture_size: u32,
+    /// The largest size of the window's framebuffer, in pixels.
+    pub max_viewport: (u32, u32),
+    /// The most anisotropic filtering available, if any.
+    pub max_anisotropy: Option<f32>,
+    pub supports_geometry_shaders: bool,
+    pub supports_tessellation_shaders: bool,
+}
+
+impl GraphicsInfo {
+    pub(crate) fn query(display: &glium::Display) -> Self {
+        use glium::{backend::Facade, CapabilitiesSource};
+
+        let context = display.get_context();
+        let caps = context.get_capabilities();
+        let glsl = context.get_supported_glsl_version();
+
+        Self {
+            gl_version: context.get_opengl_version_string().to_string(),
+            glsl_version: (glsl.1, glsl.2),
+            vendor: context.get_opengl_vendor_string().to_string(),
+            renderer: context.get_opengl_renderer_string().to_string(),
+            max_texture_size: caps.max_texture_size.max(0) as u32,
+            max_viewport: (
+                caps.max_viewport_dims.0.max(0) as u32,
+                caps.max_viewport_dims.1.max(0) as u32,
+            ),
+            max_anisotropy: caps.max_texture_max_anisotropy,
+            supports_geometry_shaders: glium::program::is_geometry_shader_supported(&**context),
+            supports_tessellation_shaders: glium::program::is_tessellation_shader_supported(
+                &**context,
+            ),
+        }
+    }
+}
+
+impl fmt::Display for GraphicsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "OpenGL: {}", self.gl_version)?;
+        writeln!(f, "GLSL: {}.{}", self.glsl_version.0, self.glsl_version.1)?;
+        writeln!(f, "renderer: {} ({})", self.renderer, self.vendor)?;
+        writeln!(f, "max texture size: {}", self.max_texture_size)?;
+        writeln!(
+            f,
+            "geometry shaders: {}, tessellation shaders: {}",
+            self.supports_geometry_shaders, self.supports_tessellation_shaders,
+        )
+    }
+}
+
+/// Loads a font from the data of a .otf / .ttf file,
+/// without creating a [`Text`](sprite::Text). The font can be
+/// cloned cheaply into many texts via [`sprite::text_with_font`].
+pub fn load_font(data: &[u8]) -> Option<FontArc> {
+    FontArc::try_from_vec(data.to_vec()).ok()
+}
+
+/// Loads a font from a .otf / .ttf file. See [`load_font`].
+pub fn load_font_from_file<S: ToString>(path: S) -> Option<FontArc> {
+    sprite::load_font_file(Path::new(&path.to_string()))
+}
 
 /// An RGBA color in byte format.
 ///
@@ -43,7 +148,8 @@ mod text;
 ///
 /// assert_eq!(color1, color2);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     /// The red channel of the color.
     pub r: u8,
@@ -56,6 +162,70 @@ pub struct Color {
 }
 
 impl Color {
+    /// Opaque black.
+    pub const BLACK: Self = Self {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    /// Opaque white.
+    pub const WHITE: Self = Self {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+    /// Opaque red.
+    pub const RED: Self = Self {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    /// Opaque green.
+    pub const GREEN: Self = Self {
+        r: 0,
+        g: 255,
+        b: 0,
+        a: 255,
+    };
+    /// Opaque blue.
+    pub const BLUE: Self = Self {
+        r: 0,
+        g: 0,
+        b: 255,
+        a: 255,
+    };
+    /// Opaque yellow.
+    pub const YELLOW: Self = Self {
+        r: 255,
+        g: 255,
+        b: 0,
+        a: 255,
+    };
+    /// Opaque cyan.
+    pub const CYAN: Self = Self {
+        r: 0,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+    /// Opaque magenta.
+    pub const MAGENTA: Self = Self {
+        r: 255,
+        g: 0,
+        b: 255,
+        a: 255,
+    };
+    /// Fully transparent black.
+    pub const TRANSPARENT: Self = Self {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+
     /// Creates an opaque white color. Use the builder
     /// pattern to adjust the color.
     #[inline]
@@ -106,6 +276,112 @@ impl Color {
             self.a as f32 / 255.0,
         ]
     }
+
+    /// Creates a color from a hue (in degrees, wrapping around
+    /// every 360), saturation and value (both 0.0-1.0).
+    pub fn from_hsv(h: f32, s: f32, v: f32, a: u8) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+
+        Self::from_chroma(h, chroma, v - chroma, a)
+    }
+
+    /// Creates a color from a hue (in degrees, wrapping around
+    /// every 360), saturation and lightness (both 0.0-1.0).
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: u8) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+
+        Self::from_chroma(h, chroma, l - chroma / 2.0, a)
+    }
+
+    /// Gets the hue (in degrees, 0.0-360.0), saturation and
+    /// value (both 0.0-1.0) of the color, ignoring opacity.
+    /// Grays have a hue and saturation of 0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_f32();
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+
+        (h, s, max)
+    }
+
+    /// Shared by `from_hsv` and `from_hsl`: builds a color
+    /// from a hue, chroma, and the amount added to every channel.
+    fn from_chroma(h: f32, chroma: f32, m: f32, a: u8) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f32| ((c + m) * 255.0).round() as u8;
+
+        Self::new(channel(r), channel(g), channel(b), a)
+    }
+
+    /// Linearly interpolates each channel from this color to
+    /// `other`. `t` is clamped to 0.0-1.0; channels are rounded
+    /// to the nearest value.
+    pub fn lerp(&self, other: &Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        Self {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+            a: channel(self.a, other.a),
+        }
+    }
+
+    /// Parses a hex color: `rrggbb`, `rrggbbaa`, or the short
+    /// `rgb`, with or without a leading `#`, in either case.
+    /// Colors without an alpha are opaque. Returns `None` if
+    /// the string is malformed.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok();
+        match hex.len() {
+            3 => {
+                let short = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|c| c * 17);
+                Some(Self::new(short(0)?, short(1)?, short(2)?, 255))
+            }
+            6 => Some(Self::new(channel(0)?, channel(1)?, channel(2)?, 255)),
+            8 => Some(Self::new(
+                channel(0)?,
+                channel(1)?,
+                channel(2)?,
+                channel(3)?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Formats the color as `#rrggbbaa`, in lowercase.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
 }
 
 impl Default for Color {
@@ -123,6 +399,11 @@ impl Default for Color {
 ///
 /// Defaults to `1`.
 ///
+/// Sprites at the same depth are drawn in order of their
+/// [`Order`](crate::ecs::Order) (lower first, so higher ones
+/// end up on top), without changing their depth. Use it for
+/// e.g. the stacking of overlapping cards or inventory slots.
+///
 /// ```
 /// # use genji::prelude::*;
 /// # struct FakeWorld;
@@ -138,6 +419,7 @@ impl Default for Color {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Depth(pub u32);
 
 impl Deref for Depth {
@@ -154,6 +436,32 @@ impl DerefMut for Depth {
     }
 }
 
+/// Marks a sprite as an overlay, e.g. a tooltip or dialog.
+///
+/// Overlays ignore [`Depth`] (even `Depth(0)`), and are drawn
+/// after every other sprite, by [`Order`](crate::ecs::Order)
+/// and then by entity id. Entity ids only follow spawn order
+/// until something is despawned, since freed ids are reused,
+/// so give overlays an `Order` if they overlap each other.
+///
+/// ```
+/// # use genji::prelude::*;
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) {}
+/// # }
+/// # let world = FakeWorld;
+/// # fn some_sprite() -> () { () }
+///
+/// world.spawn((
+///     some_sprite(),
+///     AlwaysOnTop,
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlwaysOnTop;
+
 /// The angle of a sprite.
 ///
 /// Defaults to `0.0`.
@@ -173,6 +481,7 @@ impl DerefMut for Depth {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Angle(pub f32);
 
 impl Deref for Angle {
@@ -209,6 +518,7 @@ impl DerefMut for Angle {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fill(pub bool);
 
 impl Deref for Fill {
@@ -225,7 +535,8 @@ impl DerefMut for Fill {
     }
 }
 
-/// The weight of the lines if `!fill` (only for shapes).
+/// The weight of the outline, in coordinates, if `!fill`.
+/// The outline is centered on the edges of the sprite.
 ///
 /// Defaults to `4`.
 ///
@@ -244,6 +555,7 @@ impl DerefMut for Fill {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StrokeWeight(pub u32);
 
 impl Deref for StrokeWeight {
@@ -259,3 +571,310 @@ impl DerefMut for StrokeWeight {
         &mut self.0
     }
 }
+
+/// An outline drawn over a shape ([`Rect`](crate::shape::Rect),
+/// [`Circle`](crate::shape::Circle), or [`Triangle`](crate::shape::Triangle)),
+/// in addition to the shape itself. This way, a filled shape
+/// can have a border of a different color, e.g. for a button.
+///
+/// The outline uses the sprite's [`StrokeJoin`] and [`StrokeCap`].
+///
+/// ```
+/// # use genji::prelude::*;
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) {}
+/// # }
+/// # let world = FakeWorld;
+///
+/// world.spawn((
+///     shape::rect(80, 30),
+///     Point(0, 0),
+///     Color::new(40, 40, 40, 255),
+///     Stroke {
+///         color: Color::new(255, 255, 255, 255),
+///         weight: 2,
+///     },
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+    pub color: Color,
+    pub weight: u32,
+}
+
+/// How the corners of an outline are drawn, if `!fill`.
+///
+/// Defaults to `Miter`.
+///
+/// ```
+/// # use genji::prelude::*;
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) {}
+/// # }
+/// # let world = FakeWorld;
+/// # fn some_sprite() -> () { () }
+///
+/// world.spawn((
+///     some_sprite(),
+///     Fill(false),
+///     StrokeJoin::Round,
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeJoin {
+    /// Extends the edges until they meet in a sharp point.
+    /// Very sharp corners fall back to `Bevel`.
+    #[default]
+    Miter,
+    /// Rounds off the corners.
+    Round,
+    /// Cuts the corners off flat.
+    Bevel,
+}
+
+/// How the ends of an open outline (e.g. a line) are drawn.
+/// Closed outlines, such as those of rects, have no ends.
+///
+/// Defaults to `Butt`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeCap {
+    /// Ends the outline exactly at its endpoints.
+    #[default]
+    Butt,
+    /// Extends the outline past its endpoints by half its weight.
+    Square,
+    /// Rounds off the ends of the outline.
+    Round,
+}
+
+/// Makes a sprite's [`Point`] and [`Angle`] relative to
+/// another entity's, so that it moves and rotates with it.
+///
+/// If the parent has no [`Point`] (e.g. it was despawned),
+/// the sprite is positioned as if it had no parent.
+///
+/// ```
+/// # use genji::prelude::*;
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) -> Entity { todo!() }
+/// # }
+/// # let world = FakeWorld;
+/// # fn some_sprite() -> () { () }
+///
+/// let tank = world.spawn((
+///     some_sprite(),
+///     Point(100, 0),
+///     Angle(45.0),
+/// ));
+///
+/// // Sits 20 units "above" the tank, rotated with it.
+/// world.spawn((
+///     some_sprite(),
+///     Point(0, 20),
+///     Parent(tank),
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parent(pub Entity);
+
+impl Deref for Parent {
+    type Target = Entity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Parent {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// How many [`Parent`]s deep genji will look before
+/// giving up (e.g. in case of a cycle).
+const MAX_PARENT_DEPTH: usize = 64;
+
+/// Converts an entity's local position and angle into
+/// world-space by composing them with its [`Parent`]s.
+pub(crate) fn resolve_transform(
+    world: &World,
+    id: Entity,
+    mut pos: Point,
+    mut angle: f32,
+) -> (Point, f32) {
+    let mut current = id;
+    for _ in 0..MAX_PARENT_DEPTH {
+        let Ok(parent) = world.get::<&Parent>(current).map(|p| **p) else {
+            break;
+        };
+
+        let Ok(parent_pos) = world.get::<&Point>(parent).map(|p| *p) else {
+            break;
+        };
+
+        let parent_angle = world.get::<&Angle>(parent).map(|a| **a).unwrap_or(0.0);
+
+        // Sprites rotate clockwise, but `pivot` rotates counter-clockwise.
+        pos = shape::pivot(pos + parent_pos, -parent_angle, parent_pos);
+        angle += parent_angle;
+        current = parent;
+    }
+
+    (pos, angle)
+}
+
+/// Warns about sprites without a `Point` (or `FPoint`), which are
+/// never drawn. Each entity is only warned about once while it
+/// stays unplaced; `warned` tracks which ones have been.
+pub(crate) fn warn_unplaced(world: &World, warned: &mut HashSet<Entity>) {
+    // Forget entities that were despawned or given a position,
+    // so that the set doesn't grow forever.
+    warned.retain(|&id| matches!(world.satisfies::<Or<&Point, &FPoint>>(id), Ok(false)));
+
+    macro_rules! warn_unplaced {
+        ( $( $sprite_type:ident ),* ) => {$(
+            let mut query = world.query::<crate::ecs::Without<&$sprite_type, Or<&Point, &FPoint>>>();
+            for (id, _) in query.iter() {
+                if warned.insert(id) {
+                    helpers::report_error(&format!(
+                        "warning: {} sprite on entity {id:?} has no Point, so it won't be drawn",
+                        stringify!($sprite_type),
+                    ));
+                }
+            }
+        )*};
+    }
+
+    warn_unplaced!(Rect, Circle, Triangle, Text, Texture);
+}
+
+/// A region of the screen with its own camera, for
+/// split-screen. See `GameState.viewports`.
+///
+/// The scene is drawn once per viewport, offset by its
+/// camera. Each viewport is still as many coordinates tall
+/// as the whole screen (see `GameState.virtual_size`), so
+/// set the camera's `view` to match the region's shape.
+///
+/// ```
+/// # use genji::{prelude::*, graphics::Viewport, util::Camera};
+/// # let mut state = GameState::new((), "", None, None, None, None);
+/// let players = Viewport::split_vertical(
+///     Camera::new(Point(-100, 0)),
+///     Camera::new(Point(100, 0)),
+/// );
+/// state.viewports = players.to_vec();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+    /// The region, as `(x, y, w, h)` fractions (`0.0`-`1.0`)
+    /// of the screen, from the top-left.
+    pub rect: (f32, f32, f32, f32),
+    pub camera: Camera,
+    /// The color this region is cleared to. Defaults to None,
+    /// using `GameState.clear_color`.
+    pub clear_color: Option<Color>,
+}
+
+impl Viewport {
+    /// Creates a viewport covering `rect` (see [`Viewport::rect`]).
+    pub fn new(rect: (f32, f32, f32, f32), camera: Camera) -> Self {
+        Self {
+            rect,
+            camera,
+            clear_color: None,
+        }
+    }
+
+    /// Creates a viewport covering the whole screen.
+    pub fn full(camera: Camera) -> Self {
+        Self::new((0.0, 0.0, 1.0, 1.0), camera)
+    }
+
+    /// Splits the screen into a left and a right half.
+    pub fn split_vertical(left: Camera, right: Camera) -> [Self; 2] {
+        [
+            Self::new((0.0, 0.0, 0.5, 1.0), left),
+            Self::new((0.5, 0.0, 0.5, 1.0), right),
+        ]
+    }
+
+    /// Splits the screen into a top and a bottom half.
+    pub fn split_horizontal(top: Camera, bottom: Camera) -> [Self; 2] {
+        [
+            Self::new((0.0, 0.0, 1.0, 0.5), top),
+            Self::new((0.0, 0.5, 1.0, 0.5), bottom),
+        ]
+    }
+
+    /// Sets the color this region is cleared to.
+    pub fn clear_color(mut self, color: Color) -> Self {
+        self.clear_color = Some(color);
+        self
+    }
+
+    /// Returns the region in pixels on a screen of `size`, as
+    /// `(left, bottom, w, h)` from the bottom-left (like OpenGL).
+    pub fn pixels(&self, size: (u32, u32)) -> (u32, u32, u32, u32) {
+        let (x, y, w, h) = self.rect;
+        let (sw, sh) = (size.0 as f32, size.1 as f32);
+
+        // Rounding the edges (not the sizes) keeps
+        // neighbouring viewports from gapping or overlapping.
+        let left = (x.clamp(0.0, 1.0) * sw).round();
+        let right = ((x + w).clamp(0.0, 1.0) * sw).round();
+        let top = (y.clamp(0.0, 1.0) * sh).round();
+        let bottom = ((y + h).clamp(0.0, 1.0) * sh).round();
+
+        (
+            left as u32,
+            (sh - bottom) as u32,
+            (right - left).max(0.0) as u32,
+            (bottom - top).max(0.0) as u32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{ecs::World, helpers::tests::reported, shape};
+
+    use super::{warn_unplaced, Point};
+
+    #[test]
+    fn unplaced_sprites_are_warned_about_once() {
+        let mut world = World::new();
+        let mut warned = HashSet::new();
+        let lost = world.spawn((shape::rect(4, 4),));
+        let placed = world.spawn((shape::rect(4, 4), Point(0, 0)));
+        let warning = |id| reported(&format!("on entity {id:?} has no Point"));
+        reported("");
+
+        warn_unplaced(&world, &mut warned);
+        warn_unplaced(&world, &mut warned);
+        assert_eq!(warning(lost).len(), 1);
+        assert!(warning(placed).is_empty());
+
+        world.insert_one(lost, Point(0, 0)).unwrap();
+        warn_unplaced(&world, &mut warned);
+        assert!(warned.is_empty());
+
+        world.remove_one::<Point>(lost).unwrap();
+        warn_unplaced(&world, &mut warned);
+        assert_eq!(warning(lost).len(), 2);
+
+        world.despawn(lost).unwrap();
+        warn_unplaced(&world, &mut warned);
+        assert!(warned.is_empty());
+    }
+}
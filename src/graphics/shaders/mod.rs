@@ -1,12 +1,21 @@
 use glium::{Display, Program};
 
+use super::{
+    sprite::{CircleCache, TextureCache},
+    text::GlyphCache,
+};
+
 const SHAPE: (&str, &str) = (include_str!("shape.vert"), include_str!("shape.frag"));
 const TEXTURE: (&str, &str) = (include_str!("texture.vert"), include_str!("texture.frag"));
 
-/// A "singleton" holding all of the pre-compiled shaders.
+/// A "singleton" holding all of the pre-compiled shaders,
+/// as well as the textures, glyphs and circles uploaded for them.
 pub struct Shaders {
     pub shape: Program,
     pub texture: Program,
+    pub textures: TextureCache,
+    pub glyphs: GlyphCache,
+    pub circles: CircleCache,
 }
 
 impl Shaders {
@@ -16,6 +25,9 @@ impl Shaders {
             shape: Program::from_source(d, SHAPE.0, SHAPE.1, None).expect("error in shape shaders"),
             texture: Program::from_source(d, TEXTURE.0, TEXTURE.1, None)
                 .expect("error in texture shaders"),
+            textures: TextureCache::default(),
+            glyphs: GlyphCache::default(),
+            circles: CircleCache::default(),
         }
     }
 }
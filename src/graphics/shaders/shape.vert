@@ -5,8 +5,9 @@ in vec4 color;
 out vec4 v_color;
 
 uniform mat4 matrix;
+uniform vec4 tint;
 
 void main() {
-    v_color = color;
+    v_color = color * tint;
     gl_Position = matrix * vec4(position, 1.0, 1.0);
 }
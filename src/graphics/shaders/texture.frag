@@ -4,7 +4,27 @@ in vec2 v_tex_coords;
 out vec4 color;
 
 uniform sampler2D tex;
+// The part of `tex` that's drawn, as (x, y, w, h).
+uniform vec4 region;
+// How coordinates past the edges of `region` are wrapped:
+// 0 leaves it to the sampler (for whole images), and 1, 2
+// and 3 clamp, repeat, and mirror within the region.
+uniform int wrap;
 
 void main() {
-    color = v_color * texture(tex, v_tex_coords);
+    vec2 uv = v_tex_coords;
+    if (wrap != 0) {
+        if (wrap == 2) {
+            uv = fract(uv);
+        } else if (wrap == 3) {
+            uv = 1.0 - abs(mod(uv, 2.0) - 1.0);
+        }
+
+        // Stay half a texel inside the region, so that
+        // neighbouring regions never bleed into it.
+        vec2 half_texel = 0.5 / (region.zw * vec2(textureSize(tex, 0)));
+        uv = clamp(uv, half_texel, 1.0 - half_texel);
+    }
+
+    color = v_color * texture(tex, region.xy + uv * region.zw);
 }
@@ -7,32 +7,44 @@
 //! [`Triangle`],
 //! [`Text`],
 //! and [`Texture`].
+//!
+//! Any of them can be turned into a ready-to-spawn
+//! [`SpriteBundle`] via [`SpriteExt::at`]. Your own types
+//! can be spawned like sprites by implementing [`IntoSprite`].
 
 // TODO: can texture initialization take advantage of
 // TODO: Arc<[_]> to avoid re-allocation?
 
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     f32::consts::PI,
     fmt::Debug,
     fs::File,
     io::{BufReader, Cursor, Read},
-    path::Path,
-    sync::Arc,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Weak},
 };
 
-use super::{shaders, text, Color};
+use super::{shaders, text, Angle, Color, Depth, Fill, StrokeCap, StrokeJoin, StrokeWeight};
 
 use crate::{
-    helpers::gj2gl,
-    shape::{Circle, Rect, Triangle},
+    ecs::{Component, DynamicBundle},
+    helpers::{self, gj2gl},
+    shape::{Circle, FPoint, Point, Rect, Triangle},
 };
 
 use ab_glyph::FontArc;
+use image::RgbaImage;
 use shaders::Shaders;
 
 use glium::{
-    implement_vertex, texture::RawImage2d, uniform, Blend, Display, Frame, PolygonMode, Surface,
-    VertexBuffer,
+    implement_vertex,
+    texture::RawImage2d,
+    uniform,
+    uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerWrapFunction},
+    Blend, BlendingFunction, Display, LinearBlendingFactor, Surface, VertexBuffer,
 };
 
 /// An image format enum for loading images from
@@ -49,29 +61,53 @@ struct Vertex {
 implement_vertex!(Vertex, position, color, tex_coords);
 
 /// Used to sort sprites before rendering.
+#[derive(Clone, Copy)]
 pub(crate) enum Sprite<'a> {
     Rect(&'a Rect),
     Circle(&'a Circle),
     Triangle(&'a Triangle),
     Text(&'a Text),
     Texture(&'a Texture),
+    Batch(&'a Batch),
 }
 
 impl<'a> Sprite<'a> {
-    pub(crate) fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
+    /// Whether or not the sprite can be outlined with a [`Stroke`](super::Stroke).
+    pub(crate) fn is_shape(&self) -> bool {
+        matches!(self, Self::Rect(_) | Self::Circle(_) | Self::Triangle(_))
+    }
+
+    pub(crate) fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
         match self {
             Self::Rect(sprite) => sprite.draw(target, ex, d, shaders),
             Self::Circle(sprite) => sprite.draw(target, ex, d, shaders),
             Self::Triangle(sprite) => sprite.draw(target, ex, d, shaders),
             Self::Text(sprite) => sprite.draw(target, ex, d, shaders),
             Self::Texture(sprite) => sprite.draw(target, ex, d, shaders),
+            Self::Batch(sprite) => sprite.draw(target, ex, d, shaders),
         }
     }
 }
 
-/// The data required to draw a sprite.
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct SpriteData {
+/// The data required to draw a sprite, gathered from its
+/// components. Use the builder pattern to adjust it.
+///
+/// ```
+/// # use genji::prelude::*;
+/// let ex = SpriteData::new()
+///     .pos(Point(20, -30))
+///     .angle(45.0)
+///     .color(Color::new(255, 0, 0, 255))
+///     .depth(3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteData {
     /// The horizontal position of the sprite.
     /// Defaults to `0`.
     pub x: i32,
@@ -84,15 +120,29 @@ pub(crate) struct SpriteData {
     /// The rotation of the sprite, in degrees.
     /// Defaults to `0.0`.
     pub angle: f32,
-    /// Whether or not to fill the sprite (only for shapes).
+    /// Whether or not to fill the sprite.
     /// Defaults to `true`.
     pub fill: bool,
-    /// The weight of the lines if `!fill` (only for shapes).
+    /// The weight of the outline if `!fill`.
     /// Defaults to `4`.
     pub stroke_weight: u32,
+    /// How the corners of the outline are drawn if `!fill`.
+    /// Defaults to `StrokeJoin::Miter`.
+    pub join: StrokeJoin,
+    /// How the ends of an open outline are drawn if `!fill`.
+    /// Defaults to `StrokeCap::Butt`.
+    pub cap: StrokeCap,
     /// The color of the sprite (for sprites, offsets the color).
     /// Defaults to opaque white.
     pub color: Color,
+    /// How far past `x` and `y` the sprite is drawn, for
+    /// positions between coordinates (see [`FPoint`]).
+    /// Defaults to `(0.0, 0.0)`.
+    pub frac: (f32, f32),
+    /// How many times bigger than its `font_size` text is drawn,
+    /// around its position. Set from `GameState.ui_scale`.
+    /// Defaults to `1.0`.
+    pub text_scale: f32,
 }
 
 impl SpriteData {
@@ -102,6 +152,91 @@ impl SpriteData {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Sets the position of the sprite.
+    pub fn pos(mut self, pos: Point) -> Self {
+        self.x = pos.0;
+        self.y = pos.1;
+        self.frac = (0.0, 0.0);
+        self
+    }
+
+    /// Sets the position of the sprite, between coordinates.
+    pub fn fpos(mut self, pos: FPoint) -> Self {
+        let floor = pos.floor();
+        self.x = floor.0;
+        self.y = floor.1;
+        self.frac = (pos.0 - floor.0 as f32, pos.1 - floor.1 as f32);
+        self
+    }
+
+    /// Moves the sprite to the nearest multiple of `grid`
+    /// coordinates (including `frac`), dropping any fraction.
+    /// A `grid` of `0` does nothing. See `GameState.snap_to_grid`.
+    pub fn snap_to_grid(mut self, grid: u32) -> Self {
+        if grid == 0 {
+            return self;
+        }
+
+        let grid = grid as f32;
+        let snap = |pos: i32, frac: f32| ((pos as f32 + frac) / grid).round() as i32 * grid as i32;
+        self.x = snap(self.x, self.frac.0);
+        self.y = snap(self.y, self.frac.1);
+        self.frac = (0.0, 0.0);
+        self
+    }
+
+    /// The last row of the sprite's transformation matrix.
+    fn translation(&self) -> [f32; 4] {
+        [
+            gj2gl::coordf(self.x as f32 + self.frac.0),
+            gj2gl::coordf(self.y as f32 + self.frac.1),
+            0.0,
+            1.0,
+        ]
+    }
+
+    /// Sets the z-level of the sprite. `0` hides it.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the rotation of the sprite, in degrees.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Sets whether or not to fill the sprite.
+    pub fn fill(mut self, fill: bool) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Sets the weight of the outline if `!fill`.
+    pub fn stroke_weight(mut self, stroke_weight: u32) -> Self {
+        self.stroke_weight = stroke_weight;
+        self
+    }
+
+    /// Sets how the corners of the outline are drawn.
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets how the ends of an open outline are drawn.
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets the color of the sprite.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
 }
 
 impl Default for SpriteData {
@@ -113,11 +248,187 @@ impl Default for SpriteData {
             fill: true,
             angle: 0.0,
             stroke_weight: 4,
+            join: StrokeJoin::default(),
+            cap: StrokeCap::default(),
             color: Color::default(),
+            frac: (0.0, 0.0),
+            text_scale: 1.0,
         }
     }
 }
 
+/// A sprite along with its components, ready to be spawned.
+/// Created via [`SpriteExt::at`], so a sprite can't be
+/// spawned without a position by mistake.
+///
+/// ```
+/// # use genji::prelude::*;
+/// let mut world = World::new();
+///
+/// world.spawn(
+///     shape::rect(20, 10)
+///         .at(0, 50)
+///         .color(Color::new(255, 0, 0, 255))
+///         .depth(3)
+///         .build(),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpriteBundle<S> {
+    pub sprite: S,
+    pub data: SpriteData,
+}
+
+impl<S> SpriteBundle<S> {
+    /// Sets the z-level of the sprite. `0` hides it.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.data.depth = depth;
+        self
+    }
+
+    /// Sets the rotation of the sprite, in degrees.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.data.angle = angle;
+        self
+    }
+
+    /// Sets whether or not to fill the sprite.
+    pub fn fill(mut self, fill: bool) -> Self {
+        self.data.fill = fill;
+        self
+    }
+
+    /// Sets the weight of the outline if `!fill`.
+    pub fn stroke_weight(mut self, stroke_weight: u32) -> Self {
+        self.data.stroke_weight = stroke_weight;
+        self
+    }
+
+    /// Sets how the corners of the outline are drawn.
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.data.join = join;
+        self
+    }
+
+    /// Sets how the ends of an open outline are drawn.
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.data.cap = cap;
+        self
+    }
+
+    /// Sets the color of the sprite.
+    pub fn color(mut self, color: Color) -> Self {
+        self.data.color = color;
+        self
+    }
+
+    /// Turns the bundle into a tuple of components
+    /// that can be passed to `World::spawn`.
+    pub fn build(
+        self,
+    ) -> (
+        S,
+        Point,
+        Depth,
+        Angle,
+        Fill,
+        StrokeWeight,
+        StrokeJoin,
+        StrokeCap,
+        Color,
+    ) {
+        let ex = self.data;
+
+        (
+            self.sprite,
+            Point(ex.x, ex.y),
+            Depth(ex.depth),
+            Angle(ex.angle),
+            Fill(ex.fill),
+            StrokeWeight(ex.stroke_weight),
+            ex.join,
+            ex.cap,
+            ex.color,
+        )
+    }
+}
+
+/// Lets sprites be turned into a [`SpriteBundle`].
+pub trait SpriteExt: Sized {
+    /// Places the sprite at `(x, y)`, returning a bundle
+    /// that can be customized further.
+    fn at(self, x: i32, y: i32) -> SpriteBundle<Self> {
+        SpriteBundle {
+            sprite: self,
+            data: SpriteData::new().pos(Point(x, y)),
+        }
+    }
+}
+
+/// Turns a type into the components of an entity, so games
+/// can define their own high-level entities (a `Button`, an
+/// `Enemy`, ...) on top of genji's components. Spawn one with
+/// [`WorldExt::spawn_sprite`](crate::ecs::WorldExt::spawn_sprite).
+///
+/// The bundle is a flat tuple of components; extra components
+/// go in that tuple too, or can be inserted afterwards with
+/// [`World::insert`](crate::ecs::World::insert). Nesting tuples
+/// doesn't work, since a tuple is a component of its own.
+///
+/// ```
+/// # use genji::prelude::*;
+/// struct Clickable;
+///
+/// struct Button {
+///     x: i32,
+///     y: i32,
+///     color: Color,
+/// }
+///
+/// impl IntoSprite for Button {
+///     type Bundle = (Rect, Point, Color, Depth, Clickable);
+///
+///     fn into_sprite(self) -> Self::Bundle {
+///         (shape::rect(80, 20), Point(self.x, self.y), self.color, Depth(10), Clickable)
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let button = world.spawn_sprite(Button { x: 0, y: 0, color: Color::new(0, 0, 255, 255) });
+/// world.insert_one(button, Fill(false)).unwrap();
+/// ```
+pub trait IntoSprite {
+    /// The components the type turns into.
+    type Bundle: DynamicBundle;
+
+    /// Turns the value into its components.
+    fn into_sprite(self) -> Self::Bundle;
+}
+
+impl<S: Component> IntoSprite for SpriteBundle<S> {
+    type Bundle = (
+        S,
+        Point,
+        Depth,
+        Angle,
+        Fill,
+        StrokeWeight,
+        StrokeJoin,
+        StrokeCap,
+        Color,
+    );
+
+    fn into_sprite(self) -> Self::Bundle {
+        self.build()
+    }
+}
+
+impl SpriteExt for Rect {}
+impl SpriteExt for Circle {}
+impl SpriteExt for Triangle {}
+impl SpriteExt for Text {}
+impl SpriteExt for Texture {}
+
 /// A text sprite.
 ///
 /// A font must be passed at creation
@@ -127,6 +438,10 @@ impl Default for SpriteData {
 /// to load a font from a file using
 /// `text_font_from_file` instead of `font`.
 ///
+/// Glyphs are rasterized once per font and size, and
+/// reused from a shared atlas, so changing the text
+/// every frame is cheap.
+///
 /// ```
 /// # use genji::{ecs::World, graphics::Point};
 /// # struct FakeWorld;
@@ -149,6 +464,112 @@ pub struct Text {
     pub text: String,
     pub font: FontArc,
     pub font_size: f32,
+    /// The file the font was loaded from, if any, as a
+    /// canonical path.
+    pub font_path: Option<PathBuf>,
+    /// How many times larger than its displayed size the
+    /// text is rendered, to reduce blur. Higher values look
+    /// sharper but cost more to draw.
+    ///
+    /// Defaults to `1.0`.
+    pub quality: f32,
+    /// A filled box drawn behind the text, sized to fit it,
+    /// as its color and the padding around the text (in
+    /// coordinates). Useful for subtitles and tooltips.
+    ///
+    /// Defaults to None.
+    pub background: Option<(Color, u32)>,
+    /// The width, in pixels at `font_size`, at which lines
+    /// are wrapped onto the next one. Words are kept whole
+    /// unless a single word is wider than this.
+    ///
+    /// Defaults to None, only breaking lines at newlines.
+    pub max_width: Option<f32>,
+}
+
+impl Text {
+    /// Lays out the text, wrapping it at `max_width`, and returns
+    /// the box around each line. Measurements are in pixels at
+    /// `font_size`, relative to the top-left of the text.
+    ///
+    /// Useful for e.g. scrolling through a log, or finding
+    /// which line was clicked.
+    pub fn layout_lines(&self, max_width: f32) -> Vec<LineBox> {
+        text::layout_lines(&self.font, self.font_size, max_width, &self.text)
+    }
+
+    /// Returns the byte index of the character under `local`,
+    /// a point relative to the text's position (i.e. its center),
+    /// accounting for kerning and line breaks. Returns None if
+    /// the point isn't over a character, e.g. past the end of
+    /// a line.
+    ///
+    /// Useful for placing a cursor by clicking, or for links.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// # fn dummy(text: &sprite::Text, state: &GameState<()>, pos: Point) {
+    /// let mouse = Point(state.mouse_x, state.mouse_y);
+    /// if let Some(i) = text.glyph_at(mouse - pos) {
+    ///     println!("clicked on {:?}", &text.text[i..]);
+    /// }
+    /// # }
+    /// ```
+    pub fn glyph_at(&self, local: Point) -> Option<usize> {
+        let (width, height) = self.pixel_size();
+
+        // Text is drawn centered, at half a coordinate per
+        // pixel, with y going up instead of down.
+        let x = local.0 as f32 * 2.0 + width / 2.0;
+        let y = height / 2.0 - local.1 as f32 * 2.0;
+
+        text::glyph_at(
+            &self.font,
+            self.font_size,
+            self.wrap_width(),
+            &self.text,
+            x,
+            y,
+        )
+    }
+
+    /// Sets the box drawn behind the text. See [`Text::background`].
+    pub fn with_background(mut self, color: Color, padding: u32) -> Self {
+        self.background = Some((color, padding));
+        self
+    }
+
+    /// Wraps the text at `width` pixels. See [`Text::max_width`].
+    pub fn wrap(mut self, width: f32) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// The width lines wrap at, or infinity if they don't.
+    fn wrap_width(&self) -> f32 {
+        self.max_width.unwrap_or(f32::INFINITY)
+    }
+
+    /// The width and height of the laid out text, in pixels.
+    fn pixel_size(&self) -> (f32, f32) {
+        text::measure_exact(&self.font, self.font_size, self.wrap_width(), &self.text)
+    }
+}
+
+/// A single line of laid out [`Text`].
+/// See [`Text::layout_lines`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineBox {
+    /// The byte index of the first character of the line.
+    pub start: usize,
+    /// The byte index just past the last character of the line.
+    pub end: usize,
+    /// How far the top of the line is from the top of the text.
+    pub y: f32,
+    /// The width of the line.
+    pub width: f32,
+    /// The height of the line.
+    pub height: f32,
 }
 
 /// A texture sprite.
@@ -182,10 +603,191 @@ pub struct Text {
 /// ```
 #[derive(Debug, Clone)]
 pub struct Texture {
+    /// The RGBA pixels of the image. It's uploaded to the GPU
+    /// once and reused every frame (and by every texture sharing
+    /// it); replacing it, or editing it through
+    /// [`set_pixel`](Self::set_pixel) or [`fill`](Self::fill),
+    /// uploads it again.
+    ///
+    /// Edit it in place only through those methods: the upload
+    /// is keyed by the data's address, so changes made some other
+    /// way (e.g. with `Arc::get_mut`) are never uploaded.
     pub data: Arc<[u8]>,
     pub dimensions: (u32, u32),
     pub w: i32,
     pub h: i32,
+    /// The file the image was loaded from, if any, as a
+    /// canonical path.
+    pub path: Option<PathBuf>,
+    /// Whether or not the color channels of the data are already
+    /// multiplied by its alpha. Set this for premultiplied images
+    /// to avoid dark fringes around transparent edges.
+    ///
+    /// Defaults to `false`.
+    pub premultiplied: bool,
+    /// The part of `data` to draw, as `(x, y, w, h)` in pixels
+    /// from the top-left. This lets many textures share one
+    /// image, such as the tiles of a [`Spritemap`](super::spritemap::Spritemap).
+    ///
+    /// Defaults to None, drawing the whole image.
+    pub region: Option<(u32, u32, u32, u32)>,
+    /// How the image is sampled past its edges, i.e. whether
+    /// or not it tiles. Defaults to `Wrap::Clamp`.
+    pub wrap: Wrap,
+    /// How many times the image fits across the sprite,
+    /// horizontally and vertically. Values above `1.0` tile
+    /// the image (or just `region`, if it's set), unless `wrap`
+    /// is `Clamp`.
+    ///
+    /// Defaults to `(1.0, 1.0)`.
+    pub tiles: (f32, f32),
+    /// Shifts which part of the image is shown, as a fraction of
+    /// its size (with `y` pointing down, like `region`), e.g. for
+    /// a scrolling background. If `region` is set, this is a
+    /// fraction of the region instead.
+    ///
+    /// Defaults to `(0.0, 0.0)`.
+    pub scroll: (f32, f32),
+    /// Bumped whenever `data` is changed in place,
+    /// so that the uploaded copy gets replaced.
+    pub(crate) revision: u64,
+}
+
+/// How a [`Texture`] is sampled past the edges of its image.
+///
+/// ```
+/// # use genji::graphics::sprite::{self, Wrap};
+/// # let data = vec![0; 16 * 16 * 4];
+/// // A background that repeats 4 times across, and 2 times down.
+/// let mut background = sprite::texture_raw(data, (16, 16), Some(640), Some(480));
+/// background.wrap = Wrap::Repeat;
+/// background.tiles = (4.0, 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Wrap {
+    /// Stretches the edge pixels.
+    #[default]
+    Clamp,
+    /// Tiles the image.
+    Repeat,
+    /// Tiles the image, flipping every other tile.
+    Mirror,
+}
+
+impl From<Wrap> for SamplerWrapFunction {
+    fn from(wrap: Wrap) -> Self {
+        match wrap {
+            Wrap::Clamp => Self::Clamp,
+            Wrap::Repeat => Self::Repeat,
+            Wrap::Mirror => Self::Mirror,
+        }
+    }
+}
+
+impl Texture {
+    /// Changes the size the texture is drawn at, without
+    /// touching its data. `w` and `h` work like they do
+    /// for [`texture`], relative to `region` if it's set.
+    pub fn resize(&mut self, w: Option<i32>, h: Option<i32>) {
+        let dimensions = self.region.map_or(self.dimensions, |(_, _, w, h)| (w, h));
+        (self.w, self.h) = texture_size(dimensions, w, h);
+    }
+
+    /// Repeats the texture, at its current size, to fill `region`,
+    /// e.g. for a floor or a wall; lighter than a tilemap for a
+    /// uniform surface. Sets `w`, `h`, and `wrap` (to `Repeat`),
+    /// and multiplies `tiles`.
+    ///
+    /// Only a whole image can be repeated, so if `region` is set
+    /// (e.g. for a tile of a [`Spritemap`](super::spritemap::Spritemap)),
+    /// the region is copied out into its own image. That copy
+    /// has no `path`, so it isn't hot-reloaded.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// # fn dummy(brick: sprite::Texture, world: &mut World) {
+    /// // A 16x16 brick, repeated 25 times across and 3 times up.
+    /// let wall = brick.tiled(shape::rect(400, 48));
+    /// world.spawn((wall, Point(0, -176)));
+    /// # }
+    /// ```
+    pub fn tiled(mut self, region: Rect) -> Self {
+        let tile = |size: i32, across: i32| match size {
+            0 => 1.0,
+            size => across as f32 / size as f32,
+        };
+
+        self.tiles.0 *= tile(self.w, region.w);
+        self.tiles.1 *= tile(self.h, region.h);
+        self.w = region.w;
+        self.h = region.h;
+        self.wrap = Wrap::Repeat;
+
+        if let Some((x, y, w, h)) = self.region.take() {
+            let (sheet_w, sheet_h) = self.dimensions;
+            let x = x.min(sheet_w);
+            let y = y.min(sheet_h);
+            let w = w.min(sheet_w - x);
+            let h = h.min(sheet_h - y);
+
+            let row = |r: u32| {
+                let start = ((y + r) as usize * sheet_w as usize + x as usize) * 4;
+                &self.data[start..start + w as usize * 4]
+            };
+            self.data = (0..h).flat_map(row).copied().collect();
+            self.dimensions = (w, h);
+            self.path = None;
+        }
+        self
+    }
+
+    /// Returns the RGBA color of the pixel at `(x, y)`,
+    /// counted from the top-left of the whole image
+    /// (ignoring `region`), or None if it's out of bounds.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        let i = self.pixel_index(x, y)?;
+        self.data.get(i..i + 4)?.try_into().ok()
+    }
+
+    /// Sets the RGBA color of the pixel at `(x, y)`,
+    /// counted from the top-left of the whole image
+    /// (ignoring `region`). Does nothing if it's out of bounds.
+    ///
+    /// If the data is shared with other textures (e.g. the
+    /// tiles of a spritemap), this texture gets its own copy first.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let Some(i) = self.pixel_index(x, y) else {
+            return;
+        };
+
+        if let Some(pixel) = self.data_mut().get_mut(i..i + 4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    /// Sets every pixel of the whole image to `color`.
+    pub fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.data_mut().chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    fn pixel_index(&self, x: u32, y: u32) -> Option<usize> {
+        let (w, h) = self.dimensions;
+        (x < w && y < h).then(|| (y as usize * w as usize + x as usize) * 4)
+    }
+
+    /// Returns the data for writing, copying it first if it's shared.
+    fn data_mut(&mut self) -> &mut [u8] {
+        if Arc::get_mut(&mut self.data).is_none() {
+            self.data = self.data.to_vec().into();
+        }
+
+        // Copied data has a new address, but data changed in place
+        // needs to be told apart from what was uploaded before.
+        self.revision += 1;
+        Arc::get_mut(&mut self.data).expect("texture data was just made unique")
+    }
 }
 
 /// Creates a [`Text`] from static data.
@@ -213,11 +815,38 @@ pub struct Texture {
 pub fn text<S: ToString>(text: S, font_data: &'static [u8], font_size: f32) -> Option<Text> {
     let font = FontArc::try_from_slice(font_data).ok()?;
 
-    Some(Text {
+    Some(text_with_font(text, font, font_size))
+}
+
+/// Creates a [`Text`] with an already loaded font, e.g.
+/// from [`load_font`](super::load_font). Cloning a [`FontArc`]
+/// doesn't copy it, so many texts can share one font.
+///
+/// ```
+/// # use genji::{ecs::World, graphics::{self, Point, sprite}};
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) {}
+/// # }
+/// # let world = FakeWorld;
+/// # fn dummy(world: FakeWorld) -> Option<()> {
+/// let font = graphics::load_font_from_file("font.ttf")?;
+///
+/// world.spawn((sprite::text_with_font("Score", font.clone(), 12.0), Point(0, 180)));
+/// world.spawn((sprite::text_with_font("Lives", font, 12.0), Point(0, 160)));
+/// # Some(())
+/// # }
+/// ```
+pub fn text_with_font<S: ToString>(text: S, font: FontArc, font_size: f32) -> Text {
+    Text {
         text: text.to_string(),
         font,
         font_size,
-    })
+        font_path: None,
+        quality: 1.0,
+        background: None,
+        max_width: None,
+    }
 }
 
 /// Creates a [`Text`] with a font file.
@@ -246,19 +875,51 @@ pub fn text_font_from_file<S1: ToString, S2: ToString>(
     font: S2,
     font_size: f32,
 ) -> Option<Text> {
-    let mut font_file = File::open(font.to_string()).ok()?;
-    let mut font_data = Vec::new();
-    font_file.read_to_end(&mut font_data).ok()?;
-
-    let font = FontArc::try_from_vec(font_data).ok()?;
+    let font_path = PathBuf::from(font.to_string());
+    let font = load_font_file(&font_path)?;
+    let font_path = font_path.canonicalize().unwrap_or(font_path);
 
     Some(Text {
         text: text.to_string(),
         font,
         font_size,
+        font_path: Some(font_path),
+        quality: 1.0,
+        background: None,
+        max_width: None,
     })
 }
 
+/// Loads a font from a .otf / .ttf file.
+pub(crate) fn load_font_file(path: &Path) -> Option<FontArc> {
+    let mut font_file = File::open(path).ok()?;
+    let mut font_data = Vec::new();
+    font_file.read_to_end(&mut font_data).ok()?;
+
+    FontArc::try_from_vec(font_data).ok()
+}
+
+/// Loads an image file.
+///
+/// If `fmt` is None, the format is derived from the file's
+/// extension, falling back to guessing from its contents.
+pub(crate) fn load_image_file(path: &Path, fmt: Option<ImageFormat>) -> Option<RgbaImage> {
+    let mut data = Vec::new();
+    BufReader::new(File::open(path).ok()?)
+        .read_to_end(&mut data)
+        .ok()?;
+
+    let fmt = fmt
+        .or_else(|| ImageFormat::from_extension(path.extension()?))
+        .or_else(|| image::guess_format(&data).ok())?;
+
+    Some(
+        image::load_from_memory_with_format(&data, fmt)
+            .ok()?
+            .to_rgba8(),
+    )
+}
+
 /// Creates a [`Texture`] from binary data.
 ///
 /// `w` and `h` work like HTML image dimensions;
@@ -266,7 +927,8 @@ pub fn text_font_from_file<S1: ToString, S2: ToString>(
 /// If neither, the image keeps a 1px:1coord ratio.
 ///
 /// You must pass an [`ImageFormat`]
-/// (borrowed from [`image`]).
+/// (borrowed from [`image`]). Returns None (and reports
+/// the error) if the data isn't a valid image.
 ///
 /// ```
 /// # use genji::{ecs::World, graphics::{Point, sprite::ImageFormat}};
@@ -293,13 +955,34 @@ where
     let data = data.into();
 
     let data = image::load(Cursor::new(data), fmt)
-        .expect("failed to make tex")
+        .map_err(|e| helpers::report_error(&format!("failed to load texture: {e}")))
+        .ok()?
         .to_rgba8();
-    // let data = image::load(Cursor::new(data), fmt).ok()?.to_rgba8();
 
     let dimensions = data.dimensions();
 
-    let (w, h) = match (w, h) {
+    let (w, h) = texture_size(dimensions, w, h);
+
+    Some(Texture {
+        data: data.into_raw().into(),
+        dimensions,
+        w,
+        h,
+        path: None,
+        premultiplied: false,
+        region: None,
+        revision: 0,
+        wrap: Wrap::Clamp,
+        tiles: (1.0, 1.0),
+        scroll: (0.0, 0.0),
+    })
+}
+
+/// Works out the size of a texture from its image's `dimensions`,
+/// like HTML image dimensions: if only one of `w` and `h` is
+/// given, the other is scaled to match.
+pub(crate) fn texture_size(dimensions: (u32, u32), w: Option<i32>, h: Option<i32>) -> (i32, i32) {
+    match (w, h) {
         (None, None) => (dimensions.0 as i32, dimensions.1 as i32),
         (None, Some(h)) => (
             (dimensions.0 as f32 * (h as f32 / dimensions.1 as f32)).round() as i32,
@@ -310,14 +993,33 @@ where
             (dimensions.1 as f32 * (w as f32 / dimensions.0 as f32)).round() as i32,
         ),
         (Some(w), Some(h)) => (w, h),
-    };
+    }
+}
 
-    Some(Texture {
-        data: data.into_raw().into(),
+/// Creates a [`Texture`] showing `region` (`x, y, w, h`)
+/// of `data`, sharing it rather than copying it.
+pub(crate) fn texture_region(
+    data: &Arc<[u8]>,
+    dimensions: (u32, u32),
+    region: (u32, u32, u32, u32),
+    w: Option<i32>,
+    h: Option<i32>,
+) -> Texture {
+    let (w, h) = texture_size((region.2, region.3), w, h);
+
+    Texture {
+        data: data.clone(),
         dimensions,
         w,
         h,
-    })
+        path: None,
+        premultiplied: false,
+        region: Some(region),
+        revision: 0,
+        wrap: Wrap::Clamp,
+        tiles: (1.0, 1.0),
+        scroll: (0.0, 0.0),
+    }
 }
 
 /// Creates a [`Texture`] from raw pixel data.
@@ -353,29 +1055,30 @@ where
 {
     let data = data.into();
 
-    let (w, h) = match (w, h) {
-        (None, None) => (dimensions.0 as i32, dimensions.1 as i32),
-        (None, Some(h)) => (
-            (dimensions.0 as f32 * (h as f32 / dimensions.1 as f32)).round() as i32,
-            h,
-        ),
-        (Some(w), None) => (
-            w,
-            (dimensions.1 as f32 * (w as f32 / dimensions.0 as f32)).round() as i32,
-        ),
-        (Some(w), Some(h)) => (w, h),
-    };
+    let (w, h) = texture_size(dimensions, w, h);
 
     Texture {
         data: data.into(),
         dimensions,
         w,
         h,
+        path: None,
+        premultiplied: false,
+        region: None,
+        revision: 0,
+        wrap: Wrap::Clamp,
+        tiles: (1.0, 1.0),
+        scroll: (0.0, 0.0),
     }
 }
 
 /// Creates a [`Texture`] from an image file.
 ///
+/// The format is derived from the file's extension. If the
+/// extension is missing or unknown, genji tries to guess the
+/// format from the file's contents. To specify the format
+/// explicitly, use [`texture_from_file_with_format`].
+///
 /// `w` and `h` work like HTML image dimensions;
 /// if only one is specified, the other is scaled to match.
 /// If neither, the image keeps a 1px:1coord ratio.
@@ -399,62 +1102,514 @@ where
 /// ));
 /// ```
 pub fn texture_from_file<S: ToString>(path: S, w: Option<i32>, h: Option<i32>) -> Option<Texture> {
-    let path = path.to_string();
-    let data = image::load(
-        BufReader::new(File::open(&path).ok()?),
-        image::ImageFormat::from_extension(
-            Path::new(&path)
-                .extension()
-                .map(|e| e.to_str().unwrap_or(""))?,
-        )?,
-    )
-    .ok()?
-    .to_rgba8();
+    texture_from_path(PathBuf::from(path.to_string()), None, w, h)
+}
 
+/// Creates a [`Texture`] from an image file, ignoring
+/// the file's extension in favor of `fmt`.
+///
+/// Useful for files that are mislabeled or have no extension.
+/// See [`texture_from_file`] for details.
+///
+/// ```
+/// # use genji::{ecs::World, graphics::{Point, sprite::ImageFormat}};
+/// # struct FakeWorld;
+/// # impl FakeWorld {
+/// #   pub fn spawn<T>(&self, x: T) {}
+/// # }
+/// # let world = FakeWorld;
+/// # mod sprite {
+/// #   use genji::graphics::sprite::ImageFormat;
+/// #   pub fn texture_from_file_with_format(path: (), f: ImageFormat, w: Option<i32>, h: Option<i32>) -> () { () }
+/// # }
+/// # let path = ();
+///
+/// world.spawn((
+///     sprite::texture_from_file_with_format(path, ImageFormat::Tga, Some(300), None),
+///     Point(0, 0),
+/// ));
+/// ```
+pub fn texture_from_file_with_format<S: ToString>(
+    path: S,
+    fmt: ImageFormat,
+    w: Option<i32>,
+    h: Option<i32>,
+) -> Option<Texture> {
+    texture_from_path(PathBuf::from(path.to_string()), Some(fmt), w, h)
+}
+
+fn texture_from_path(
+    path: PathBuf,
+    fmt: Option<ImageFormat>,
+    w: Option<i32>,
+    h: Option<i32>,
+) -> Option<Texture> {
+    let data = load_image_file(&path, fmt)?;
     let dimensions = data.dimensions();
+    let path = path.canonicalize().unwrap_or(path);
 
-    let (w, h) = match (w, h) {
-        (None, None) => (dimensions.0 as i32, dimensions.1 as i32),
-        (None, Some(h)) => (
-            (dimensions.0 as f32 * (h as f32 / dimensions.1 as f32)).round() as i32,
-            h,
-        ),
-        (Some(w), None) => (
-            w,
-            (dimensions.1 as f32 * (w as f32 / dimensions.0 as f32)).round() as i32,
-        ),
-        (Some(w), Some(h)) => (w, h),
-    };
+    let (w, h) = texture_size(dimensions, w, h);
 
     Some(Texture {
         data: data.into_raw().into(),
         dimensions,
         w,
         h,
+        path: Some(path),
+        premultiplied: false,
+        region: None,
+        revision: 0,
+        wrap: Wrap::Clamp,
+        tiles: (1.0, 1.0),
+        scroll: (0.0, 0.0),
     })
 }
 
+/// Textures that have already been uploaded to the GPU, keyed by
+/// the address of their data. Textures sharing data (e.g. the
+/// tiles of a spritemap) are only uploaded once.
+#[derive(Default)]
+pub(crate) struct TextureCache(RefCell<HashMap<usize, CachedTexture>>);
+
+/// An uploaded texture, along with the data (and revision
+/// of that data) it was uploaded from.
+type CachedTexture = (Weak<[u8]>, u64, Rc<glium::Texture2d>);
+
+impl TextureCache {
+    /// Returns the uploaded texture for `data`, uploading it if needed.
+    pub fn get(
+        &self,
+        d: &Display,
+        data: &Arc<[u8]>,
+        revision: u64,
+        dimensions: (u32, u32),
+    ) -> Rc<glium::Texture2d> {
+        let key = Arc::as_ptr(data) as *const u8 as usize;
+        let mut cache = self.0.borrow_mut();
+
+        // While the old data is alive, its address can't be reused.
+        if let Some((old, old_revision, texture)) = cache.get(&key) {
+            if old.strong_count() > 0
+                && *old_revision == revision
+                && texture.dimensions() == dimensions
+            {
+                return texture.clone();
+            }
+        }
+
+        let raw = RawImage2d::from_raw_rgba_reversed(data, dimensions);
+        let texture = Rc::new(glium::Texture2d::new(d, raw).unwrap());
+        cache.insert(key, (Arc::downgrade(data), revision, texture.clone()));
+
+        texture
+    }
+
+    /// Frees the textures whose data no longer exists.
+    pub fn prune(&self) {
+        self.0
+            .borrow_mut()
+            .retain(|_, (data, _, _)| data.strong_count() > 0);
+    }
+}
+
+/// The vertex buffers of circles drawn recently, so identical
+/// circles (same radius and stroke) share one buffer instead
+/// of building it again every frame. Buffers are white and
+/// measured in coordinates, so they don't depend on the color
+/// or on the size of the screen.
+#[derive(Default)]
+pub(crate) struct CircleCache(RefCell<HashMap<CircleKey, CachedCircle>>);
+
+/// Everything the vertices of a circle depend on: its radius,
+/// whether it's filled, and its stroke weight, join and cap.
+type CircleKey = (i32, bool, u32, StrokeJoin, StrokeCap);
+
+/// A circle's vertex buffer, along with whether or
+/// not it was used since the last prune.
+type CachedCircle = (bool, Rc<VertexBuffer<Vertex>>);
+
+impl CircleCache {
+    /// How many circles can be cached at once. Past this, new
+    /// circles are built every frame until the cache is pruned.
+    const MAX: usize = 1024;
+
+    /// Returns the vertex buffer for `key`, building it from
+    /// `vertices` if needed.
+    fn get(
+        &self,
+        d: &Display,
+        key: CircleKey,
+        vertices: impl FnOnce() -> Vec<Vertex>,
+    ) -> Rc<VertexBuffer<Vertex>> {
+        let mut cache = self.0.borrow_mut();
+        if let Some((used, vb)) = cache.get_mut(&key) {
+            *used = true;
+            return vb.clone();
+        }
+
+        let vb = Rc::new(VertexBuffer::new(d, &vertices()).unwrap());
+        if cache.len() < Self::MAX {
+            cache.insert(key, (true, vb.clone()));
+        }
+
+        vb
+    }
+
+    /// Frees the buffers that weren't used since the last prune.
+    pub fn prune(&self) {
+        self.0
+            .borrow_mut()
+            .retain(|_, (used, _)| std::mem::take(used));
+    }
+}
+
 pub(crate) trait DrawSprite {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders);
+    /// Draws the sprite, returning how many draw calls
+    /// that took and how many vertices were drawn.
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize);
 }
 
-impl DrawSprite for Rect {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
-        let mut params = glium::DrawParameters {
-            blend: Blend::alpha_blending(),
+/// The sharpest corner (as a multiple of half the stroke weight)
+/// that a stroke will miter before it's beveled instead.
+const MITER_LIMIT: f32 = 4.0;
+
+/// The largest angle (in radians) that a single triangle of
+/// a round join or cap may cover.
+const ROUND_STEP: f32 = PI / 16.0;
+
+fn add(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale(a: [f32; 2], s: f32) -> [f32; 2] {
+    [a[0] * s, a[1] * s]
+}
+
+fn length(a: [f32; 2]) -> f32 {
+    (a[0] * a[0] + a[1] * a[1]).sqrt()
+}
+
+/// The unit vector pointing to the left of the line `from` -> `to`.
+fn normal(from: [f32; 2], to: [f32; 2]) -> [f32; 2] {
+    let d = sub(to, from);
+    let len = length(d);
+    if len == 0.0 {
+        [0.0, 0.0]
+    } else {
+        [-d[1] / len, d[0] / len]
+    }
+}
+
+/// Adds a fan of triangles around `center`, sweeping the
+/// offset `from` by `sweep` radians (counter-clockwise).
+fn arc(tris: &mut Vec<[f32; 2]>, center: [f32; 2], from: [f32; 2], sweep: f32) {
+    let steps = (sweep.abs() / ROUND_STEP).ceil().max(1.0) as usize;
+    let start = from[1].atan2(from[0]);
+    let r = length(from);
+
+    let mut last = add(center, from);
+    for i in 1..=steps {
+        let a = start + sweep * i as f32 / steps as f32;
+        let next = [center[0] + r * a.cos(), center[1] + r * a.sin()];
+        tris.extend([center, last, next]);
+        last = next;
+    }
+}
+
+/// Builds a list of triangles that outline `points` (as a
+/// polygon if `closed`, otherwise as a line), centered on its
+/// edges and `weight` units thick.
+///
+/// Outlines are built as geometry rather than relying on
+/// `line_width`, since many drivers don't support wide lines.
+fn stroke(
+    points: &[[f32; 2]],
+    closed: bool,
+    weight: f32,
+    join: StrokeJoin,
+    cap: StrokeCap,
+) -> Vec<[f32; 2]> {
+    let half = weight / 2.0;
+    let n = points.len();
+    let mut tris = Vec::new();
+
+    if n < 2 || half <= 0.0 {
+        return tris;
+    }
+
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let o = scale(normal(a, b), half);
+
+        tris.extend([add(a, o), sub(a, o), add(b, o)]);
+        tris.extend([sub(a, o), sub(b, o), add(b, o)]);
+    }
+
+    let corners = if closed { 0..n } else { 1..n - 1 };
+    for i in corners {
+        let cur = points[i];
+        let n1 = normal(points[(i + n - 1) % n], cur);
+        let n2 = normal(cur, points[(i + 1) % n]);
+
+        let cross = n1[0] * n2[1] - n1[1] * n2[0];
+        if cross.abs() <= f32::EPSILON {
+            continue;
+        }
+
+        // The gap between the segments is on the outside of the turn.
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let o1 = scale(n1, half * side);
+        let o2 = scale(n2, half * side);
+
+        match join {
+            StrokeJoin::Bevel => tris.extend([cur, add(cur, o1), add(cur, o2)]),
+            StrokeJoin::Miter => {
+                tris.extend([cur, add(cur, o1), add(cur, o2)]);
+
+                let m = add(n1, n2);
+                let m_len = length(m);
+                let cos = (m[0] * n2[0] + m[1] * n2[1]) / m_len;
+                let miter = half / cos;
+                if m_len > 0.0 && miter <= half * MITER_LIMIT {
+                    let tip = add(cur, scale(m, side * miter / m_len));
+                    tris.extend([add(cur, o1), tip, add(cur, o2)]);
+                }
+            }
+            StrokeJoin::Round => {
+                let dot = o1[0] * o2[0] + o1[1] * o2[1];
+                let sweep = (o1[0] * o2[1] - o1[1] * o2[0]).atan2(dot);
+                arc(&mut tris, cur, o1, sweep);
+            }
+        }
+    }
+
+    if !closed {
+        for (end, inner) in [(points[0], points[1]), (points[n - 1], points[n - 2])] {
+            // `o` is to the left when facing out of the line.
+            let o = scale(normal(inner, end), half);
+            let out = [o[1], -o[0]];
+
+            match cap {
+                StrokeCap::Butt => {}
+                StrokeCap::Square => {
+                    tris.extend([add(end, o), sub(end, o), add(add(end, o), out)]);
+                    tris.extend([sub(end, o), add(sub(end, o), out), add(add(end, o), out)]);
+                }
+                StrokeCap::Round => arc(&mut tris, end, o, -PI),
+            }
+        }
+    }
+
+    tris
+}
+
+/// The draw parameters shared by every sprite.
+///
+/// Sprites are sorted back-to-front by depth and drawn in
+/// painter's order, so the depth buffer is neither tested nor
+/// written: a translucent sprite must blend with whatever is
+/// drawn over it later, rather than hide it. The depth in each
+/// sprite's matrix only places it within the clip volume.
+fn draw_parameters(blend: Blend) -> glium::DrawParameters<'static> {
+    glium::DrawParameters {
+        blend,
+        depth: glium::Depth {
+            test: glium::DepthTest::Overwrite,
+            write: false,
             ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Blending for colors whose channels are already
+/// multiplied by their alpha.
+fn premultiplied_alpha_blending() -> Blend {
+    let function = BlendingFunction::Addition {
+        source: LinearBlendingFactor::One,
+        destination: LinearBlendingFactor::OneMinusSourceAlpha,
+    };
+
+    Blend {
+        color: function,
+        alpha: function,
+        constant_value: (0.0, 0.0, 0.0, 0.0),
+    }
+}
+
+/// Maps a position on a `w` by `h` quad (centered on
+/// the origin) to texture coordinates.
+fn quad_tex_coords(pos: [f32; 2], w: f32, h: f32) -> [f32; 2] {
+    let u = if w == 0.0 {
+        0.5
+    } else {
+        pos[0] / (w * 2.0) + 0.5
+    };
+    let v = if h == 0.0 {
+        0.5
+    } else {
+        pos[1] / (h * 2.0) + 0.5
+    };
+    [u, v]
+}
+
+/// Builds the vertices for a `w` by `h` quad, either filled
+/// or outlined via [`stroke`], as a list of triangles.
+fn quad_vertices(w: f32, h: f32, ex: &SpriteData) -> Vec<Vertex> {
+    let color = ex.color.to_f32();
+
+    let positions = if ex.fill {
+        vec![[-w, h], [w, h], [-w, -h], [w, h], [w, -h], [-w, -h]]
+    } else {
+        stroke(
+            &[[-w, h], [w, h], [w, -h], [-w, -h]],
+            true,
+            gj2gl::coord(ex.stroke_weight as i32),
+            ex.join,
+            ex.cap,
+        )
+    };
+
+    positions
+        .into_iter()
+        .map(|position| Vertex {
+            position,
+            color,
+            tex_coords: quad_tex_coords(position, w, h),
+        })
+        .collect()
+}
+
+/// A sprite queued for a single frame via `GameState`'s
+/// immediate-mode methods, like [`GameState::draw_rect`].
+///
+/// [`GameState::draw_rect`]: crate::state::GameState::draw_rect
+#[derive(Clone, Debug)]
+pub(crate) enum Queued {
+    Batch(Batch),
+    Rect(Rect),
+    Text(Text),
+}
+
+impl Queued {
+    pub(crate) fn sprite(&self) -> Sprite<'_> {
+        match self {
+            Self::Batch(batch) => Sprite::Batch(batch),
+            Self::Rect(rect) => Sprite::Rect(rect),
+            Self::Text(text) => Sprite::Text(text),
+        }
+    }
+}
+
+/// Points queued via [`GameState::draw_points`] or
+/// [`GameState::draw_polyline`], drawn in a single call.
+///
+/// [`GameState::draw_points`]: crate::state::GameState::draw_points
+/// [`GameState::draw_polyline`]: crate::state::GameState::draw_polyline
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Batch {
+    pub(crate) points: Vec<Point>,
+    /// Whether the points are joined into a line (using the
+    /// stroke weight, join, and cap), or drawn as dots.
+    pub(crate) line: bool,
+}
+
+impl DrawSprite for Batch {
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let (s_width, s_height) = target.get_dimensions();
+
+        // Dots are one coordinate wide.
+        let params = glium::DrawParameters {
+            point_size: Some((s_height as f32 / helpers::screen_height() as f32).max(1.0)),
+            ..draw_parameters(Blend::alpha_blending())
         };
 
-        let color = ex.color.to_f32();
+        let ratio = s_height as f32 / s_width as f32;
+        let mat = [
+            [ratio, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
+            ex.translation(),
+        ];
+
+        let uniforms = uniform! {
+            matrix: mat,
+            tint: [1.0f32; 4],
+        };
 
-        let indices = if ex.fill {
-            glium::index::PrimitiveType::TriangleStrip
+        let points: Vec<[f32; 2]> = self
+            .points
+            .iter()
+            .map(|p| [gj2gl::coord(p.0), gj2gl::coord(p.1)])
+            .collect();
+
+        let (positions, primitive) = if self.line {
+            let weight = gj2gl::coord(ex.stroke_weight as i32);
+            (
+                stroke(&points, false, weight, ex.join, ex.cap),
+                glium::index::PrimitiveType::TrianglesList,
+            )
         } else {
-            params.polygon_mode = PolygonMode::Line;
-            params.line_width = Some(gj2gl::coord(ex.stroke_weight as i32 + 500));
-            glium::index::PrimitiveType::LineStrip
+            (points, glium::index::PrimitiveType::Points)
         };
 
+        if positions.is_empty() {
+            return (0, 0);
+        }
+
+        let color = ex.color.to_f32();
+        let vertices: Vec<Vertex> = positions
+            .into_iter()
+            .map(|position| Vertex {
+                position,
+                color,
+                tex_coords: [0.0, 0.0],
+            })
+            .collect();
+
+        let vb = VertexBuffer::new(d, &vertices).unwrap();
+
+        target
+            .draw(
+                &vb,
+                glium::index::NoIndices(primitive),
+                &shaders.shape,
+                &uniforms,
+                &params,
+            )
+            .expect("failed to draw batch");
+
+        (1, vb.len())
+    }
+}
+
+impl DrawSprite for Rect {
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let params = draw_parameters(Blend::alpha_blending());
+
         let (s_width, s_height) = target.get_dimensions();
         let ratio = s_height as f32 / s_width as f32;
         let a = -ex.angle * (PI / 180.0);
@@ -462,159 +1617,132 @@ impl DrawSprite for Rect {
             [a.cos() * ratio, a.sin(), 0.0, 0.0],
             [-a.sin(), a.cos(), 0.0, 0.0],
             [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
-            [gj2gl::coord(ex.x), gj2gl::coord(ex.y), 0.0, 1.0],
+            ex.translation(),
         ];
 
         let uniforms = uniform! {
             matrix: mat,
+            tint: [1.0f32; 4],
         };
 
         let w = gj2gl::coord(self.w) / 2.0;
         let h = gj2gl::coord(self.h) / 2.0;
 
-        let vb = if ex.fill {
-            let vertices = [
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, h],
-                    tex_coords: [1.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, -h],
-                    tex_coords: [0.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, -h],
-                    tex_coords: [1.0, 0.0],
-                    color,
-                },
-            ];
-
-            VertexBuffer::new(d, &vertices).unwrap()
-        } else {
-            let vertices = [
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, h],
-                    tex_coords: [1.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, -h],
-                    tex_coords: [1.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, -h],
-                    tex_coords: [0.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-            ];
-
-            VertexBuffer::new(d, &vertices).unwrap()
-        };
+        let vb = VertexBuffer::new(d, &quad_vertices(w, h, &ex)).unwrap();
 
         target
             .draw(
                 &vb,
-                glium::index::NoIndices(indices),
+                glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
                 &shaders.shape,
                 &uniforms,
                 &params,
             )
             .expect("failed to draw rect");
+
+        (1, vb.len())
     }
 }
 
 impl DrawSprite for Circle {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
-        let mut params = glium::DrawParameters {
-            blend: Blend::alpha_blending(),
-            ..Default::default()
-        };
-
-        let color = ex.color.to_f32();
-
-        let indices = if ex.fill {
-            glium::index::PrimitiveType::TriangleStrip
-        } else {
-            params.polygon_mode = PolygonMode::Line;
-            params.line_width = Some(gj2gl::coord(ex.stroke_weight as i32 + 500));
-            glium::index::PrimitiveType::LineStrip
-        };
-
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let params = draw_parameters(Blend::alpha_blending());
+
+        // The cached vertices are white and measured in coordinates,
+        // so they're tinted and scaled to the screen here instead.
         let (s_width, s_height) = target.get_dimensions();
         let ratio = s_height as f32 / s_width as f32;
+        let scale = gj2gl::coordf(1.0);
         let a = -ex.angle * (PI / 180.0);
         let mat = [
-            [a.cos() * ratio, a.sin(), 0.0, 0.0],
-            [-a.sin(), a.cos(), 0.0, 0.0],
+            [a.cos() * ratio * scale, a.sin() * scale, 0.0, 0.0],
+            [-a.sin() * scale, a.cos() * scale, 0.0, 0.0],
             [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
-            [gj2gl::coord(ex.x), gj2gl::coord(ex.y), 0.0, 1.0],
+            ex.translation(),
         ];
 
         let uniforms = uniform! {
             matrix: mat,
+            tint: ex.color.to_f32(),
         };
 
-        let r = gj2gl::coord(self.r);
-        let mut vertices = Vec::new();
-
-        let mut a = 0.0f32;
-        while a <= 360.0 {
-            let pos = [r * a.cos(), r * a.sin()];
-            vertices.push(Vertex {
-                position: pos,
-                color,
-                tex_coords: [pos[0] + 0.5, pos[1] + 0.5],
-            });
-
-            if ex.fill && a % 1.0 == 0.0 {
+        let key = (self.r, ex.fill, ex.stroke_weight, ex.join, ex.cap);
+        let vb = shaders.circles.get(d, key, || {
+            const SEGMENTS: usize = 180;
+            let color = [1.0; 4];
+            let r = self.r as f32;
+            let points: Vec<[f32; 2]> = (0..SEGMENTS)
+                .map(|i| {
+                    let a = i as f32 / SEGMENTS as f32 * 2.0 * PI;
+                    [r * a.cos(), r * a.sin()]
+                })
+                .collect();
+            let mut vertices = Vec::new();
+
+            if ex.fill {
+                // A fan around the center, closed by
+                // repeating the first point on the rim.
                 vertices.push(Vertex {
                     position: [0.0, 0.0],
                     color,
                     tex_coords: [0.5, 0.5],
                 });
+                for &pos in points.iter().chain(points.first()) {
+                    vertices.push(Vertex {
+                        position: pos,
+                        color,
+                        tex_coords: [pos[0] + 0.5, pos[1] + 0.5],
+                    });
+                }
+            } else {
+                let weight = ex.stroke_weight as f32;
+                for pos in stroke(&points, true, weight, ex.join, ex.cap) {
+                    vertices.push(Vertex {
+                        position: pos,
+                        color,
+                        tex_coords: [pos[0] + 0.5, pos[1] + 0.5],
+                    });
+                }
             }
 
-            a += 0.5;
-        }
+            vertices
+        });
 
-        let vb = VertexBuffer::new(d, &vertices).unwrap();
+        let primitive = if ex.fill {
+            glium::index::PrimitiveType::TriangleFan
+        } else {
+            glium::index::PrimitiveType::TrianglesList
+        };
 
         target
             .draw(
-                &vb,
-                glium::index::NoIndices(indices),
+                &*vb,
+                glium::index::NoIndices(primitive),
                 &shaders.shape,
                 &uniforms,
                 &params,
             )
             .expect("failed to draw rect");
+
+        (1, vb.len())
     }
 }
 
 impl DrawSprite for Triangle {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
-        let params = glium::DrawParameters {
-            blend: Blend::alpha_blending(),
-            ..Default::default()
-        };
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let params = draw_parameters(Blend::alpha_blending());
 
         let color = ex.color.to_f32();
 
@@ -625,11 +1753,12 @@ impl DrawSprite for Triangle {
             [a.cos() * ratio, a.sin(), 0.0, 0.0],
             [-a.sin(), a.cos(), 0.0, 0.0],
             [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
-            [gj2gl::coord(ex.x), gj2gl::coord(ex.y), 0.0, 1.0],
+            ex.translation(),
         ];
 
         let uniforms = uniform! {
             matrix: mat,
+            tint: [1.0f32; 4],
         };
 
         let w = gj2gl::coord(self.w) / 2.0;
@@ -664,25 +1793,39 @@ impl DrawSprite for Triangle {
                 &params,
             )
             .expect("failed to draw triangle");
+
+        (1, vb.len())
     }
 }
 
 impl DrawSprite for Text {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
-        let mut params = glium::DrawParameters {
-            blend: Blend::alpha_blending(),
-            ..Default::default()
-        };
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let scale = ex.text_scale;
+
+        // The background goes under the glyphs, centered like them,
+        // at half a coordinate per pixel.
+        let background = self.background.map_or((0, 0), |(color, padding)| {
+            let (width, height) = self.pixel_size();
+            let padding = padding as f32 * 2.0;
+            let rect = Rect {
+                w: ((width / 2.0 + padding) * scale).ceil() as i32,
+                h: ((height / 2.0 + padding) * scale).ceil() as i32,
+            };
+            let ex = SpriteData {
+                color,
+                fill: true,
+                ..ex
+            };
+            rect.draw(target, ex, d, shaders)
+        });
 
-        let color = ex.color.to_f32();
-
-        let indices = if ex.fill {
-            glium::index::PrimitiveType::TriangleStrip
-        } else {
-            params.polygon_mode = PolygonMode::Line;
-            params.line_width = Some(gj2gl::coord(ex.stroke_weight as i32 + 500));
-            glium::index::PrimitiveType::LineStrip
-        };
+        let params = draw_parameters(Blend::alpha_blending());
 
         let (s_width, s_height) = target.get_dimensions();
         let ratio = s_height as f32 / s_width as f32;
@@ -691,86 +1834,71 @@ impl DrawSprite for Text {
             [a.cos() * ratio, a.sin(), 0.0, 0.0],
             [-a.sin(), a.cos(), 0.0, 0.0],
             [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
-            [gj2gl::coord(ex.x), gj2gl::coord(ex.y), 0.0, 1.0],
+            ex.translation(),
         ];
 
-        let (buf, w, h) = text::render_glyphs(&self.font, self.font_size, &self.text, &ex);
-
-        let raw = RawImage2d::from_raw_rgba_reversed(
-            buf.into_iter()
-                .flatten()
-                .flat_map(|(r, g, b, a)| [r, g, b, a])
-                .collect::<Vec<_>>()
-                .as_slice(),
-            (w as u32, h as u32),
-        );
-
-        let texture = glium::Texture2d::new(d, raw).unwrap();
+        // Rendering glyphs bigger and scaling down the mesh
+        // results in higher quality text and less blur.
+        let quality = self.quality.max(f32::EPSILON);
+        let px = gj2gl::coord(1) * 0.5 / quality;
+        let color = ex.color.to_f32();
 
-        // Scaling down the mesh forces the font size to get bigger,
-        // which results in higher quality textures and less blur.
-        let w = gj2gl::coord(w as i32) * 0.5;
-        let h = gj2gl::coord(h as i32) * 0.5;
-        let vb = VertexBuffer::new(
+        let (draw_calls, vertices) = shaders.glyphs.with_text(
             d,
-            &[
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, h],
-                    tex_coords: [1.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, -h],
-                    tex_coords: [0.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, -h],
-                    tex_coords: [1.0, 0.0],
-                    color,
-                },
-            ],
-        )
-        .unwrap();
-
-        let uniforms = uniform! {
-            matrix: mat,
-            tex: texture,
-        };
-
-        target
-            .draw(
-                &vb,
-                glium::index::NoIndices(indices),
-                &shaders.texture,
-                &uniforms,
-                &params,
-            )
-            .expect("failed to draw texture");
+            &self.font,
+            self.font_size * quality * scale,
+            self.wrap_width() * quality * scale,
+            &self.text,
+            |texture, quads| {
+                let vertices = quads
+                    .into_iter()
+                    .flatten()
+                    .map(|([x, y], tex_coords)| Vertex {
+                        position: [x * px, y * px],
+                        color,
+                        tex_coords,
+                    })
+                    .collect::<Vec<_>>();
+
+                let vb = VertexBuffer::new(d, &vertices).unwrap();
+
+                let uniforms = uniform! {
+                    matrix: mat,
+                    tex: texture.sampled().wrap_function(SamplerWrapFunction::Clamp),
+                    region: [0.0f32, 0.0, 1.0, 1.0],
+                    wrap: 0i32,
+                };
+
+                target
+                    .draw(
+                        &vb,
+                        glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+                        &shaders.texture,
+                        &uniforms,
+                        &params,
+                    )
+                    .expect("failed to draw text");
+
+                (1, vb.len())
+            },
+        );
+        (background.0 + draw_calls, background.1 + vertices)
     }
 }
 
 impl DrawSprite for Texture {
-    fn draw(&self, target: &mut Frame, ex: SpriteData, d: &Display, shaders: &Shaders) {
-        let mut params = glium::DrawParameters {
-            blend: Blend::alpha_blending(),
-            ..Default::default()
-        };
-
-        let color = ex.color.to_f32();
-
-        let indices = if ex.fill {
-            glium::index::PrimitiveType::TriangleStrip
+    fn draw<S: Surface>(
+        &self,
+        target: &mut S,
+        ex: SpriteData,
+        d: &Display,
+        shaders: &Shaders,
+    ) -> (usize, usize) {
+        let params = draw_parameters(if self.premultiplied {
+            premultiplied_alpha_blending()
         } else {
-            params.polygon_mode = PolygonMode::Line;
-            params.line_width = Some(gj2gl::coord(ex.stroke_weight as i32 + 500));
-            glium::index::PrimitiveType::LineStrip
-        };
+            Blend::alpha_blending()
+        });
 
         let (s_width, s_height) = target.get_dimensions();
         let ratio = s_height as f32 / s_width as f32;
@@ -779,85 +1907,80 @@ impl DrawSprite for Texture {
             [a.cos() * ratio, a.sin(), 0.0, 0.0],
             [-a.sin(), a.cos(), 0.0, 0.0],
             [0.0, 0.0, (ex.depth as f32) / 256.0, 0.0],
-            [gj2gl::coord(ex.x), gj2gl::coord(ex.y), 0.0, 1.0],
+            ex.translation(),
         ];
 
-        let raw = glium::texture::RawImage2d::from_raw_rgba_reversed(&self.data, self.dimensions);
-        let texture = glium::Texture2d::new(d, raw).unwrap();
+        let texture = shaders
+            .textures
+            .get(d, &self.data, self.revision, self.dimensions);
 
         let w = gj2gl::coord(self.w) / 2.0;
         let h = gj2gl::coord(self.h) / 2.0;
 
-        let vb = if ex.fill {
-            let vertices = [
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, h],
-                    tex_coords: [1.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, -h],
-                    tex_coords: [0.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, -h],
-                    tex_coords: [1.0, 0.0],
-                    color,
-                },
+        // The data is uploaded flipped, so down the image is `-v`.
+        let mut vertices = quad_vertices(w, h, &ex);
+        for vertex in &mut vertices {
+            let [u, v] = vertex.tex_coords;
+            vertex.tex_coords = [
+                u * self.tiles.0 + self.scroll.0,
+                v * self.tiles.1 - self.scroll.1,
             ];
+        }
+        if self.premultiplied {
+            // The tint has to be premultiplied too.
+            for vertex in &mut vertices {
+                let a = vertex.color[3];
+                vertex.color[0] *= a;
+                vertex.color[1] *= a;
+                vertex.color[2] *= a;
+            }
+        }
 
-            VertexBuffer::new(d, &vertices).unwrap()
-        } else {
-            let vertices = [
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, h],
-                    tex_coords: [1.0, 1.0],
-                    color,
-                },
-                Vertex {
-                    position: [w, -h],
-                    tex_coords: [1.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, -h],
-                    tex_coords: [0.0, 0.0],
-                    color,
-                },
-                Vertex {
-                    position: [-w, h],
-                    tex_coords: [0.0, 1.0],
-                    color,
-                },
-            ];
+        let vb = VertexBuffer::new(d, &vertices).unwrap();
 
-            VertexBuffer::new(d, &vertices).unwrap()
+        // A region is wrapped by the shader instead of the sampler, and
+        // sampled without filtering so that it can't blend in texels
+        // from its neighbours on the sheet.
+        let sampler = texture.sampled().wrap_function(self.wrap.into());
+        let (sampler, region, wrap) = match self.region {
+            Some((rx, ry, rw, rh)) => {
+                let (dw, dh) = (self.dimensions.0 as f32, self.dimensions.1 as f32);
+                let region = [
+                    rx as f32 / dw,
+                    1.0 - (ry + rh) as f32 / dh,
+                    rw as f32 / dw,
+                    rh as f32 / dh,
+                ];
+                let wrap = match self.wrap {
+                    Wrap::Clamp => 1i32,
+                    Wrap::Repeat => 2,
+                    Wrap::Mirror => 3,
+                };
+                let sampler = sampler
+                    .magnify_filter(MagnifySamplerFilter::Nearest)
+                    .minify_filter(MinifySamplerFilter::Nearest);
+                (sampler, region, wrap)
+            }
+            None => (sampler, [0.0, 0.0, 1.0, 1.0], 0),
         };
 
         let uniforms = uniform! {
             matrix: mat,
-            tex: texture,
+            tex: sampler,
+            region: region,
+            wrap: wrap,
         };
 
         target
             .draw(
                 &vb,
-                glium::index::NoIndices(indices),
+                glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
                 &shaders.texture,
                 &uniforms,
                 &params,
             )
             .expect("failed to draw texture");
+
+        (1, vb.len())
     }
 }
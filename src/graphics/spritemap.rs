@@ -9,19 +9,21 @@
 //! you can retrieve arbitrarily placed and sized
 //! sprites via [`Spritemap::get_rect`].
 //!
-//! Note that retrieving a sprite from a spritemap clones
-//! the sprite data, it doesn't reference it.
+//! Sprites retrieved from a spritemap don't copy its image;
+//! they all share it, and draw only their own region of it.
+//! This way, the image is only uploaded to the GPU once.
+//!
+//! Frames for animations can be pulled out with
+//! [`Spritemap::animation`], and played with a [`SpriteAnimation`].
 
-use std::fs::File;
-use std::io::{BufReader, Cursor};
+use std::io::Cursor;
 use std::path::Path;
+use std::sync::Arc;
 
 use image::RgbaImage;
 
 use super::sprite::{self, ImageFormat};
 
-// TODO: can spritemap textures become Arc<[u8]>?
-
 /// A texture with utilities for retrieving sprites.
 ///
 /// Spritemaps require a default sprite width and
@@ -29,7 +31,7 @@ use super::sprite::{self, ImageFormat};
 /// you can retrieve arbitrarily placed and sized
 /// sprites via [`Spritemap::get_rect`].
 pub struct Spritemap {
-    tex: RgbaImage,
+    tex: Arc<[u8]>,
     dims: (u32, u32),
 
     w: u32,
@@ -46,47 +48,40 @@ impl Spritemap {
     /// `w` and `h`, returns None.
     pub fn new<D: Into<Vec<u8>>>(data: D, fmt: ImageFormat, w: u32, h: u32) -> Option<Self> {
         let data = image::load(Cursor::new(data.into()), fmt).ok()?.to_rgba8();
-
-        let dims = data.dimensions();
-
-        if dims.0 % w != 0 || dims.1 % h != 0 {
-            return None;
-        }
-
-        let sw = dims.0 / w;
-        let sh = dims.1 / h;
-
-        Some(Self {
-            tex: data,
-            dims,
-
-            w,
-            h,
-            sw,
-            sh,
-        })
+        Self::from_image(data, w, h)
     }
 
-    /// Creates a new spritemap from and image file.
+    /// Creates a new spritemap from an image file.
+    ///
+    /// The format is derived from the file's extension, falling
+    /// back to guessing from its contents.
     ///
     /// If the images dimensions do not cleanly divide into
     /// `w` and `h`, returns None.
     pub fn from_file<S: ToString>(path: S, w: u32, h: u32) -> Option<Self> {
-        let path = path.to_string();
-        let data = image::load(
-            BufReader::new(File::open(&path).ok()?),
-            ImageFormat::from_extension(
-                Path::new(&path)
-                    .extension()
-                    .map(|e| e.to_str().unwrap_or(""))?,
-            )?,
-        )
-        .ok()?
-        .to_rgba8();
+        let data = sprite::load_image_file(Path::new(&path.to_string()), None)?;
+        Self::from_image(data, w, h)
+    }
+
+    /// Creates a new spritemap from an image file, ignoring
+    /// the file's extension in favor of `fmt`.
+    ///
+    /// If the images dimensions do not cleanly divide into
+    /// `w` and `h`, returns None.
+    pub fn from_file_with_format<S: ToString>(
+        path: S,
+        fmt: ImageFormat,
+        w: u32,
+        h: u32,
+    ) -> Option<Self> {
+        let data = sprite::load_image_file(Path::new(&path.to_string()), Some(fmt))?;
+        Self::from_image(data, w, h)
+    }
 
+    fn from_image(data: RgbaImage, w: u32, h: u32) -> Option<Self> {
         let dims = data.dimensions();
 
-        if dims.0 % w != 0 || dims.1 % h != 0 {
+        if !dims.0.is_multiple_of(w) || !dims.1.is_multiple_of(h) {
             return None;
         }
 
@@ -94,7 +89,7 @@ impl Spritemap {
         let sh = dims.1 / h;
 
         Some(Self {
-            tex: data,
+            tex: data.into_raw().into(),
             dims,
 
             w,
@@ -104,37 +99,47 @@ impl Spritemap {
         })
     }
 
-    // TODO: can this be improved?
-    fn sample_rect(&self, x: u32, y: u32, w: u32, h: u32) -> Vec<u8> {
-        let mut samples = Vec::new();
-        for yy in y..(y + h) {
-            for xx in x..(x + w) {
-                samples.push((xx, yy));
-            }
-        }
-
-        let mut pb = Vec::new();
-        for (x, y) in samples {
-            pb.push(self.tex.get_pixel(x, y).0);
-        }
-
-        pb.into_iter().flatten().collect()
+    /// Creates a texture showing a region of the spritemap.
+    fn region(
+        &self,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+        tw: Option<i32>,
+        th: Option<i32>,
+    ) -> sprite::Texture {
+        sprite::texture_region(&self.tex, self.dims, (x, y, w, h), tw, th)
     }
 
     /// Get a sprite using the preset width and height options.
     pub fn get_id(&self, id: u32, w: Option<i32>, h: Option<i32>) -> Option<sprite::Texture> {
-        if id > self.sw * self.sh {
+        if id >= self.sw * self.sh {
             return None;
         }
 
         let x = (id % self.sw) * self.w;
         let y = (id / self.sw) * self.h;
 
-        let pb = self.sample_rect(x, y, self.w, self.h);
-        Some(sprite::texture_raw(pb, (self.w, self.h), w, h))
+        Some(self.region(x, y, self.w, self.h, w, h))
+    }
+
+    /// Gets a sprite for each id, in order, e.g. as the frames
+    /// of a [`SpriteAnimation`]. Returns None if any id is out
+    /// of range.
+    ///
+    /// `w` and `h` are the same as in [`Spritemap::get_id`].
+    pub fn animation(
+        &self,
+        ids: &[u32],
+        w: Option<i32>,
+        h: Option<i32>,
+    ) -> Option<Vec<sprite::Texture>> {
+        ids.iter().map(|&id| self.get_id(id, w, h)).collect()
     }
 
     /// Get a sub-region of the spritemap, ignoring usual bounds.
+    /// Returns None if the region doesn't fit in the spritemap.
     ///
     /// `tw` and `th` correspond to the `w` and `h` arguments on
     /// [`sprite::texture`].
@@ -147,11 +152,87 @@ impl Spritemap {
         tw: Option<i32>,
         th: Option<i32>,
     ) -> Option<sprite::Texture> {
-        if x + w >= self.dims.0 || y + h >= self.dims.1 {
+        let right = x.checked_add(w)?;
+        let bottom = y.checked_add(h)?;
+        if right > self.dims.0 || bottom > self.dims.1 {
             return None;
         }
 
-        let pb = self.sample_rect(x, y, w, h);
-        Some(sprite::texture_raw(pb, (w, h), tw, th))
+        Some(self.region(x, y, w, h, tw, th))
+    }
+}
+
+/// A looping sequence of frames, each shown for the same
+/// amount of time. Advance it every frame, and draw its
+/// current frame:
+///
+/// ```ignore
+/// # use genji::{prelude::*, graphics::{sprite::Texture, spritemap::SpriteAnimation}};
+/// // In init...
+/// let frames = spritemap.animation(&[0, 1, 2, 3], None, None).unwrap();
+/// let animation = SpriteAnimation::new(frames, 100).unwrap();
+/// world.spawn((animation.current().clone(), Point(0, 0), animation));
+///
+/// // In onloop...
+/// for (_, (animation, texture)) in world.query_mut::<(&mut SpriteAnimation, &mut Texture)>() {
+///     animation.advance(state.delta);
+///     *texture = animation.current().clone();
+/// }
+/// ```
+///
+/// Cloning frames is cheap, since they share the spritemap's image.
+#[derive(Debug, Clone)]
+pub struct SpriteAnimation {
+    frames: Vec<sprite::Texture>,
+    /// How long each frame is shown, in milliseconds.
+    /// `0` holds the current frame.
+    pub frame_ms: u128,
+    elapsed: u128,
+    frame: usize,
+}
+
+impl SpriteAnimation {
+    /// Creates an animation starting on its first frame.
+    /// Returns None if there are no frames.
+    pub fn new(frames: Vec<sprite::Texture>, frame_ms: u128) -> Option<Self> {
+        if frames.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            frames,
+            frame_ms,
+            elapsed: 0,
+            frame: 0,
+        })
+    }
+
+    /// The index of the frame being shown.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// The frame being shown.
+    pub fn current(&self) -> &sprite::Texture {
+        &self.frames[self.frame()]
+    }
+
+    /// Moves the animation forward by `delta_ms` milliseconds
+    /// (e.g. [`GameState.delta`](crate::state::GameState::delta)),
+    /// looping back to the first frame after the last.
+    pub fn advance(&mut self, delta_ms: u128) {
+        if self.frame_ms == 0 {
+            return;
+        }
+
+        let length = self.frame_ms * self.frames.len() as u128;
+        self.elapsed = (self.elapsed + delta_ms) % length;
+        self.frame = (self.elapsed / self.frame_ms) as usize;
+    }
+
+    /// Goes back to the first frame.
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.frame = 0;
     }
 }
@@ -1,27 +1,58 @@
-use ab_glyph::{point, Font, FontArc, Glyph, Point, PxScale, ScaleFont};
+//! Text layout, and the glyph atlases text is drawn from.
+//!
+//! Use [`measure`] to find how big text will be before
+//! creating a [`Text`](super::sprite::Text) sprite for it.
 
-use super::sprite::SpriteData;
+use std::{cell::RefCell, collections::HashMap};
 
-type Pixel = (u8, u8, u8, u8);
+use ab_glyph::{point, Font, FontArc, Glyph, GlyphId, Point, PxScale, ScaleFont};
+use glium::{texture::RawImage2d, Display, Texture2d};
+
+use super::sprite::LineBox;
 
 fn layout_paragraph<F, SF>(
     font: SF,
     position: Point,
     max_width: f32,
     text: &str,
-    target: &mut Vec<Glyph>,
-) where
+    target: &mut Vec<(usize, Glyph)>,
+) -> Vec<LineBox>
+where
     F: Font,
     SF: ScaleFont<F>,
 {
     let v_advance = font.height() + font.line_gap();
     let mut caret = position + point(0.0, font.ascent());
     let mut last_glyph: Option<Glyph> = None;
-    for c in text.chars() {
+
+    // Where the last word on the line starts, as its byte index
+    // and index in `target`, and how wide the line is without it
+    // (and the whitespace before it).
+    let mut word: Option<(usize, usize, f32)> = None;
+
+    let mut lines = Vec::new();
+    let mut line = LineBox {
+        start: 0,
+        end: 0,
+        y: 0.0,
+        width: 0.0,
+        height: font.height(),
+    };
+
+    for (i, c) in text.char_indices() {
         if c.is_control() {
             if c == '\n' {
                 caret = point(position.x, caret.y + v_advance);
                 last_glyph = None;
+                word = None;
+
+                lines.push(LineBox { end: i, ..line });
+                line = LineBox {
+                    start: i + c.len_utf8(),
+                    y: line.y + v_advance,
+                    width: 0.0,
+                    ..line
+                };
             }
             continue;
         }
@@ -31,56 +62,604 @@ fn layout_paragraph<F, SF>(
         }
         glyph.position = caret;
 
-        last_glyph = Some(glyph.clone());
         caret.x += font.h_advance(glyph.id);
 
-        if !c.is_whitespace() && caret.x > position.x + max_width {
-            caret = point(position.x, caret.y + v_advance);
-            glyph.position = caret;
-            last_glyph = None;
+        if c.is_whitespace() {
+            word = Some((i + c.len_utf8(), target.len() + 1, line.width));
+        } else if caret.x > position.x + max_width && i > line.start {
+            // Move the overflowing word onto the next line. If it's
+            // the only word on its line, break it at this glyph
+            // instead; a glyph that's already first on its line
+            // isn't moved, or it would leave an empty line behind.
+            let (start, first, width) = word.filter(|&(start, ..)| start > line.start).unwrap_or((
+                i,
+                target.len(),
+                line.width,
+            ));
+
+            let left = target
+                .get(first)
+                .map_or(glyph.position.x, |(_, g)| g.position.x);
+            let shift = point(position.x - left, v_advance);
+            for (_, moved) in &mut target[first..] {
+                moved.position += shift;
+            }
+            glyph.position += shift;
+            caret += shift;
+            word = None;
+
+            lines.push(LineBox {
+                end: start,
+                width,
+                ..line
+            });
+            line = LineBox {
+                start,
+                y: line.y + v_advance,
+                ..line
+            };
         }
 
-        target.push(glyph);
+        last_glyph = Some(glyph.clone());
+
+        line.width = caret.x - position.x;
+        target.push((i, glyph));
     }
+
+    lines.push(LineBox {
+        end: text.len(),
+        ..line
+    });
+    lines
+}
+
+pub(crate) fn layout_lines(
+    font: &FontArc,
+    font_size: f32,
+    max_width: f32,
+    text: &str,
+) -> Vec<LineBox> {
+    let scaled_font = font.as_scaled(PxScale::from(font_size));
+    layout_paragraph(
+        scaled_font,
+        point(0.0, 0.0),
+        max_width,
+        text,
+        &mut Vec::new(),
+    )
+}
+
+/// Returns the width and height, in pixels, that `text` takes
+/// up at `font_size`, rounded up. Lines are only broken at
+/// newlines. Nothing is rendered, so this is cheap.
+///
+/// Sprites are drawn at half a coordinate per pixel, so
+/// halve these to get their size in coordinates.
+///
+/// ```
+/// # use genji::graphics::{self, text};
+/// # fn dummy() -> Option<()> {
+/// let font = graphics::load_font_from_file("font.ttf")?;
+/// let (w, h) = text::measure(&font, 24.0, "Hello, world!");
+/// # None
+/// # }
+/// ```
+pub fn measure(font: &FontArc, font_size: f32, text: &str) -> (u32, u32) {
+    measure_wrapped(font, font_size, None, text)
+}
+
+/// Like [`measure`], but wraps lines wider than `max_width`
+/// pixels, as [`Text::max_width`](super::sprite::Text::max_width)
+/// does. `None` only breaks lines at newlines.
+pub fn measure_wrapped(
+    font: &FontArc,
+    font_size: f32,
+    max_width: Option<f32>,
+    text: &str,
+) -> (u32, u32) {
+    let max_width = max_width.unwrap_or(f32::INFINITY);
+    let (width, height) = measure_exact(font, font_size, max_width, text);
+
+    (width.ceil() as u32, height.ceil() as u32)
 }
 
-pub(crate) fn render_glyphs(
+/// The unrounded size of `text`, in pixels.
+pub(crate) fn measure_exact(
     font: &FontArc,
     font_size: f32,
+    max_width: f32,
     text: &str,
-    ex: &SpriteData,
-) -> (Vec<Vec<Pixel>>, usize, usize) {
-    let scale = PxScale::from(font_size);
+) -> (f32, f32) {
+    lines_size(&layout_lines(font, font_size, max_width, text))
+}
+
+/// The width and height of laid out `lines`, in pixels.
+fn lines_size(lines: &[LineBox]) -> (f32, f32) {
+    let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+    let height = lines.last().map_or(0.0, |line| line.y + line.height);
+    (width, height)
+}
 
-    let scaled_font = font.as_scaled(scale);
+/// Finds the byte index of the character under `(x, y)`,
+/// in pixels from the top-left of the text wrapped at `max_width`.
+pub(crate) fn glyph_at(
+    font: &FontArc,
+    font_size: f32,
+    max_width: f32,
+    text: &str,
+    x: f32,
+    y: f32,
+) -> Option<usize> {
+    let scaled_font = font.as_scaled(PxScale::from(font_size));
 
     let mut glyphs = Vec::new();
-    layout_paragraph(scaled_font, point(20.0, 20.0), 9999.0, text, &mut glyphs);
-
-    let glyphs_height = scaled_font.height().ceil() as usize + 50;
-    let glyphs_width = {
-        let min_x = glyphs.first().unwrap().position.x;
-        let last_glyph = glyphs.last().unwrap();
-        let max_x = last_glyph.position.x + scaled_font.h_advance(last_glyph.id);
-        (max_x - min_x).ceil() as usize
-    } + 50;
-
-    let mut buf = vec![vec![(0u8, 0u8, 0u8, 0u8); glyphs_width]; glyphs_height];
-
-    let color = ex.color.to_f32();
-    for glyph in glyphs {
-        if let Some(outlined) = scaled_font.outline_glyph(glyph) {
-            let bounds = outlined.px_bounds();
-            outlined.draw(|x, y, v| {
-                buf[y as usize + bounds.min.y as usize][x as usize + bounds.min.x as usize] = (
-                    ex.color.r,
-                    ex.color.g,
-                    ex.color.b,
-                    (color[3] * v * 382.5).clamp(0.0, 255.0) as u8,
-                );
+    let lines = layout_paragraph(scaled_font, point(0.0, 0.0), max_width, text, &mut glyphs);
+
+    let line = lines
+        .iter()
+        .find(|line| (line.y..line.y + line.height).contains(&y))?;
+
+    glyphs
+        .iter()
+        .filter(|(i, _)| (line.start..line.end).contains(i))
+        .find(|(_, glyph)| {
+            let left = glyph.position.x;
+            (left..left + scaled_font.h_advance(glyph.id)).contains(&x)
+        })
+        .map(|&(i, _)| i)
+}
+
+/// The width and height of each glyph atlas, in pixels.
+const ATLAS_SIZE: u32 = 1024;
+
+/// The empty space left around each glyph in an atlas,
+/// so that neighbors don't bleed into each other.
+const ATLAS_PADDING: u32 = 1;
+
+/// Where a glyph sits in an atlas.
+#[derive(Debug, Clone, Copy)]
+struct AtlasGlyph {
+    /// The left, bottom, width, and height of the glyph in the atlas.
+    rect: (u32, u32, u32, u32),
+    /// Where the top-left of the glyph is relative to its position.
+    offset: Point,
+}
+
+/// The atlas ran out of space.
+struct AtlasFull;
+
+/// Every glyph drawn in one font at one size, rasterized
+/// once and packed into a single texture.
+struct GlyphAtlas {
+    texture: Texture2d,
+    /// None for glyphs with nothing to draw, like spaces.
+    glyphs: HashMap<GlyphId, Option<AtlasGlyph>>,
+    /// Where the next glyph goes, and how tall the current row is.
+    cursor: (u32, u32),
+    row_height: u32,
+    /// Whether or not the atlas was used since the last prune.
+    used: bool,
+}
+
+impl GlyphAtlas {
+    fn new(d: &Display) -> Self {
+        let blank = RawImage2d::from_raw_rgba(
+            vec![0u8; (ATLAS_SIZE * ATLAS_SIZE * 4) as usize],
+            (ATLAS_SIZE, ATLAS_SIZE),
+        );
+
+        Self {
+            texture: Texture2d::new(d, blank).expect("failed to create glyph atlas"),
+            glyphs: HashMap::new(),
+            cursor: (0, 0),
+            row_height: 0,
+            used: true,
+        }
+    }
+
+    /// Forgets every glyph, so the space can be reused.
+    fn clear(&mut self) {
+        self.glyphs.clear();
+        self.cursor = (0, 0);
+        self.row_height = 0;
+    }
+
+    /// Finds a glyph in the atlas, rasterizing it if needed.
+    fn glyph<F, SF>(&mut self, font: &SF, id: GlyphId) -> Result<Option<AtlasGlyph>, AtlasFull>
+    where
+        F: Font,
+        SF: ScaleFont<F>,
+    {
+        if let Some(&glyph) = self.glyphs.get(&id) {
+            return Ok(glyph);
+        }
+
+        let outlined = font
+            .outline_glyph(id.with_scale_and_position(font.scale(), point(0.0, 0.0)))
+            .filter(|outlined| {
+                let bounds = outlined.px_bounds();
+                bounds.width() >= 1.0 && bounds.height() >= 1.0
             });
+
+        let Some(outlined) = outlined else {
+            self.glyphs.insert(id, None);
+            return Ok(None);
+        };
+
+        let bounds = outlined.px_bounds();
+        let (w, h) = (bounds.width() as u32, bounds.height() as u32);
+
+        if self.cursor.0 + w > ATLAS_SIZE {
+            self.cursor = (0, self.cursor.1 + self.row_height + ATLAS_PADDING);
+            self.row_height = 0;
+        }
+
+        if w > ATLAS_SIZE || self.cursor.1 + h > ATLAS_SIZE {
+            return Err(AtlasFull);
+        }
+
+        // Glyphs are white, and tinted when drawn.
+        let mut pixels: Vec<u8> = [255, 255, 255, 0].repeat((w * h) as usize);
+        outlined.draw(|x, y, v| {
+            pixels[((y * w + x) * 4 + 3) as usize] = (v * 255.0).clamp(0.0, 255.0) as u8;
+        });
+
+        self.texture.write(
+            glium::Rect {
+                left: self.cursor.0,
+                bottom: self.cursor.1,
+                width: w,
+                height: h,
+            },
+            RawImage2d::from_raw_rgba_reversed(&pixels, (w, h)),
+        );
+
+        let glyph = AtlasGlyph {
+            rect: (self.cursor.0, self.cursor.1, w, h),
+            offset: bounds.min,
+        };
+
+        self.cursor.0 += w + ATLAS_PADDING;
+        self.row_height = self.row_height.max(h);
+        self.glyphs.insert(id, Some(glyph));
+
+        Ok(Some(glyph))
+    }
+}
+
+/// Places each glyph with `place`, skipping the ones with nothing
+/// to draw (like spaces). If the atlas fills up, it's started over
+/// with `clear` and everything is placed again; if the text still
+/// doesn't fit, as much of it as possible is placed.
+fn place_glyphs<A, T>(
+    atlas: &mut A,
+    glyphs: &[(usize, Glyph)],
+    mut place: impl FnMut(&mut A, &Glyph) -> Result<Option<T>, AtlasFull>,
+    clear: impl Fn(&mut A),
+) -> Vec<T> {
+    let mut placed = Vec::with_capacity(glyphs.len());
+    for attempt in 0..2 {
+        placed.clear();
+        let mut full = false;
+
+        for (_, glyph) in glyphs {
+            match place(atlas, glyph) {
+                Ok(Some(item)) => placed.push(item),
+                Ok(None) => {}
+                Err(AtlasFull) => {
+                    full = true;
+                    if attempt == 0 {
+                        break;
+                    }
+                }
+            }
         }
+
+        if !full || attempt == 1 {
+            break;
+        }
+
+        clear(atlas);
     }
 
-    (buf, glyphs_width, glyphs_height)
+    placed
+}
+
+/// A textured quad for a single glyph, as `(position, tex_coords)`
+/// for each of its two triangles.
+pub(crate) type GlyphQuad = [([f32; 2], [f32; 2]); 6];
+
+/// Glyph atlases for every font and size being drawn.
+///
+/// Fonts are told apart by the address of their data,
+/// so atlases that go unused for a frame are dropped,
+/// in case that address is reused.
+#[derive(Default)]
+pub(crate) struct GlyphCache(RefCell<HashMap<(usize, usize, u32), GlyphAtlas>>);
+
+impl GlyphCache {
+    /// Lays out `text` at `font_size`, wrapped at `max_width`, and
+    /// calls `f` with the atlas texture and a quad for each visible glyph.
+    ///
+    /// Quads are measured in pixels (y up), centered on the text.
+    pub fn with_text<R>(
+        &self,
+        d: &Display,
+        font: &FontArc,
+        font_size: f32,
+        max_width: f32,
+        text: &str,
+        f: impl FnOnce(&Texture2d, Vec<GlyphQuad>) -> R,
+    ) -> R {
+        let scaled_font = font.as_scaled(PxScale::from(font_size));
+
+        let mut glyphs = Vec::new();
+        let lines = layout_paragraph(scaled_font, point(0.0, 0.0), max_width, text, &mut glyphs);
+
+        let (width, height) = lines_size(&lines);
+
+        let data = font.font_data();
+        let key = (data.as_ptr() as usize, data.len(), font_size.to_bits());
+
+        let mut cache = self.0.borrow_mut();
+        let atlas = cache.entry(key).or_insert_with(|| GlyphAtlas::new(d));
+        atlas.used = true;
+
+        let quads = place_glyphs(
+            atlas,
+            &glyphs,
+            |atlas, glyph| {
+                let Some(placed) = atlas.glyph(&scaled_font, glyph.id)? else {
+                    return Ok(None);
+                };
+
+                let (ax, ay, aw, ah) = placed.rect;
+                let left = glyph.position.x.round() + placed.offset.x - width / 2.0;
+                let top = height / 2.0 - (glyph.position.y.round() + placed.offset.y);
+                let (right, bottom) = (left + aw as f32, top - ah as f32);
+
+                let tex =
+                    |x: u32, y: u32| [x as f32 / ATLAS_SIZE as f32, y as f32 / ATLAS_SIZE as f32];
+                let (tl, tr) = (tex(ax, ay + ah), tex(ax + aw, ay + ah));
+                let (bl, br) = (tex(ax, ay), tex(ax + aw, ay));
+
+                Ok(Some([
+                    ([left, top], tl),
+                    ([right, top], tr),
+                    ([left, bottom], bl),
+                    ([right, top], tr),
+                    ([right, bottom], br),
+                    ([left, bottom], bl),
+                ]))
+            },
+            GlyphAtlas::clear,
+        );
+
+        f(&atlas.texture, quads)
+    }
+
+    /// Drops the atlases that weren't used since the last prune.
+    pub fn prune(&self) {
+        self.0
+            .borrow_mut()
+            .retain(|_, atlas| std::mem::take(&mut atlas.used));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ab_glyph::{point, v2, CodepointIdIter, Font, Glyph, GlyphId, Outline, PxScale, ScaleFont};
+
+    use ab_glyph::FontArc;
+
+    use super::{layout_paragraph, measure, measure_wrapped, place_glyphs, AtlasFull};
+
+    /// A monospace font: at a size of 10.0, every
+    /// glyph is 10 pixels wide, and lines are 10 pixels tall.
+    struct Mono;
+
+    impl Font for Mono {
+        fn units_per_em(&self) -> Option<f32> {
+            Some(10.0)
+        }
+
+        fn ascent_unscaled(&self) -> f32 {
+            8.0
+        }
+
+        fn descent_unscaled(&self) -> f32 {
+            -2.0
+        }
+
+        fn line_gap_unscaled(&self) -> f32 {
+            0.0
+        }
+
+        fn glyph_id(&self, c: char) -> GlyphId {
+            GlyphId(c as u16)
+        }
+
+        fn h_advance_unscaled(&self, _: GlyphId) -> f32 {
+            10.0
+        }
+
+        fn h_side_bearing_unscaled(&self, _: GlyphId) -> f32 {
+            0.0
+        }
+
+        fn v_advance_unscaled(&self, _: GlyphId) -> f32 {
+            10.0
+        }
+
+        fn v_side_bearing_unscaled(&self, _: GlyphId) -> f32 {
+            0.0
+        }
+
+        fn kern_unscaled(&self, _: GlyphId, _: GlyphId) -> f32 {
+            0.0
+        }
+
+        fn outline(&self, _: GlyphId) -> Option<Outline> {
+            None
+        }
+
+        fn glyph_count(&self) -> usize {
+            u16::MAX as usize
+        }
+
+        fn codepoint_ids(&self) -> CodepointIdIter<'_> {
+            unimplemented!("not needed for layout")
+        }
+
+        fn glyph_raster_image2(&self, _: GlyphId, _: u16) -> Option<v2::GlyphImage<'_>> {
+            None
+        }
+    }
+
+    /// Each line's text and width, and the top-left of each glyph.
+    type Layout<'a> = (Vec<(&'a str, f32)>, Vec<(f32, f32)>);
+
+    /// Lays out `text` in [`Mono`].
+    fn layout(text: &str, max_width: f32) -> Layout<'_> {
+        let font = Mono.into_scaled(PxScale::from(10.0));
+        let mut glyphs = Vec::new();
+        let lines = layout_paragraph(&font, point(0.0, 0.0), max_width, text, &mut glyphs);
+
+        let lines = lines
+            .iter()
+            .map(|line| (&text[line.start..line.end], line.width))
+            .collect();
+        let positions = glyphs
+            .iter()
+            .map(|(_, glyph)| (glyph.position.x, glyph.position.y - font.ascent()))
+            .collect();
+        (lines, positions)
+    }
+
+    #[test]
+    fn wraps_whole_words() {
+        let (lines, positions) = layout("ab cd ef", 55.0);
+        assert_eq!(lines, [("ab cd ", 50.0), ("ef", 20.0)]);
+        assert_eq!(positions[6..], [(0.0, 10.0), (10.0, 10.0)]);
+
+        let (lines, _) = layout("ab cdef", 55.0);
+        assert_eq!(lines, [("ab ", 20.0), ("cdef", 40.0)]);
+    }
+
+    #[test]
+    fn breaks_words_wider_than_a_line() {
+        let (lines, positions) = layout("abcdefg", 35.0);
+        assert_eq!(lines, [("abc", 30.0), ("def", 30.0), ("g", 10.0)]);
+        assert_eq!(positions[3], (0.0, 10.0));
+        assert_eq!(positions[6], (0.0, 20.0));
+    }
+
+    #[test]
+    fn newlines_reset_wrapping() {
+        let (lines, _) = layout("ab\ncd ef", 45.0);
+        assert_eq!(lines, [("ab", 20.0), ("cd ", 20.0), ("ef", 20.0)]);
+
+        let (lines, _) = layout("ab cd", f32::INFINITY);
+        assert_eq!(lines, [("ab cd", 50.0)]);
+    }
+
+    #[test]
+    fn measures_wrapped_text() {
+        let font = FontArc::new(Mono);
+        assert_eq!(measure(&font, 10.0, "ab cd ef"), (80, 10));
+        assert_eq!(
+            measure_wrapped(&font, 10.0, Some(55.0), "ab cd ef"),
+            (50, 20)
+        );
+        assert_eq!(measure_wrapped(&font, 10.0, None, "ab\ncd"), (20, 20));
+    }
+
+    /// An atlas with room for `room` glyphs. Glyph `0` is a space.
+    struct FakeAtlas {
+        room: usize,
+        placed: Vec<GlyphId>,
+        clears: usize,
+    }
+
+    impl FakeAtlas {
+        fn new(room: usize) -> Self {
+            Self {
+                room,
+                placed: Vec::new(),
+                clears: 0,
+            }
+        }
+
+        fn place(&mut self, glyph: &Glyph) -> Result<Option<GlyphId>, AtlasFull> {
+            if glyph.id == GlyphId(0) {
+                return Ok(None);
+            }
+            if !self.placed.contains(&glyph.id) {
+                if self.placed.len() == self.room {
+                    return Err(AtlasFull);
+                }
+                self.placed.push(glyph.id);
+            }
+            Ok(Some(glyph.id))
+        }
+
+        fn clear(&mut self) {
+            self.placed.clear();
+            self.clears += 1;
+        }
+    }
+
+    fn glyphs(ids: &[u16]) -> Vec<(usize, Glyph)> {
+        ids.iter()
+            .enumerate()
+            .map(|(i, &id)| {
+                (
+                    i,
+                    GlyphId(id).with_scale_and_position(12.0, point(0.0, 0.0)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spaces_dont_clear_the_atlas() {
+        let mut atlas = FakeAtlas::new(8);
+        let text = glyphs(&[1, 2, 0, 3, 0, 1]);
+
+        for _ in 0..3 {
+            let placed = place_glyphs(&mut atlas, &text, FakeAtlas::place, FakeAtlas::clear);
+            assert_eq!(placed, [GlyphId(1), GlyphId(2), GlyphId(3), GlyphId(1)]);
+        }
+        assert_eq!(atlas.clears, 0);
+    }
+
+    #[test]
+    fn full_atlas_starts_over_once() {
+        let mut atlas = FakeAtlas::new(2);
+        place_glyphs(
+            &mut atlas,
+            &glyphs(&[1, 2]),
+            FakeAtlas::place,
+            FakeAtlas::clear,
+        );
+
+        let placed = place_glyphs(
+            &mut atlas,
+            &glyphs(&[3, 0, 4]),
+            FakeAtlas::place,
+            FakeAtlas::clear,
+        );
+        assert_eq!(placed, [GlyphId(3), GlyphId(4)]);
+        assert_eq!(atlas.clears, 1);
+
+        // Text that can never fit is drawn as far as possible.
+        let placed = place_glyphs(
+            &mut atlas,
+            &glyphs(&[5, 6, 7]),
+            FakeAtlas::place,
+            FakeAtlas::clear,
+        );
+        assert_eq!(placed, [GlyphId(5), GlyphId(6)]);
+        assert_eq!(atlas.clears, 2);
+    }
 }
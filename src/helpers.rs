@@ -1,29 +1,113 @@
 #![allow(dead_code)]
 
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::{cell::Cell, sync::RwLock};
 
 // use crate::graphics::Sprite;
 
+/// Where genji's error messages go. See [`set_error_handler`].
+static ERROR_HANDLER: RwLock<fn(&str)> = RwLock::new(print_error);
+
+fn print_error(message: &str) {
+    eprintln!("{message}");
+}
+
+/// Sends genji's error and warning messages (e.g. a sound
+/// that failed to play) to `handler` instead of stderr, e.g.
+/// to write them to a log file in a shipped game.
+///
+/// These are for problems genji recovers from; ones it can't
+/// recover from still panic.
+///
+/// ```
+/// fn log(message: &str) {
+///     // Write to a log file...
+/// }
+///
+/// genji::set_error_handler(log);
+/// ```
+pub fn set_error_handler(handler: fn(&str)) {
+    *ERROR_HANDLER.write().unwrap_or_else(|e| e.into_inner()) = handler;
+}
+
+/// Reports an error (or warning) via the error handler.
+pub(crate) fn report_error(message: &str) {
+    let handler = *ERROR_HANDLER.read().unwrap_or_else(|e| e.into_inner());
+    handler(message);
+}
+
+/// How tall the screen is, in coordinates, unless
+/// `GameState.virtual_size` says otherwise.
+pub(crate) const SCREEN_HEIGHT: u32 = 400;
+
+thread_local! {
+    static HEIGHT: Cell<u32> = const { Cell::new(SCREEN_HEIGHT) };
+}
+
+/// Sets how tall the screen is, in coordinates.
+/// Updated every frame, from `GameState.virtual_size`.
+pub(crate) fn set_screen_height(h: u32) {
+    HEIGHT.with(|height| height.set(h.max(1)));
+}
+
+/// How tall the screen is, in coordinates.
+pub(crate) fn screen_height() -> u32 {
+    HEIGHT.with(Cell::get)
+}
+
 /// Conversions from genji units to glutin/glium units.
 pub(crate) mod gj2gl {
-    /// Converts a genji coordinate (-400 - 400) to an OpenGL coordinate (-1.0 - 1.0).
+    /// Converts a genji coordinate (-h/2 - h/2, for a screen `h`
+    /// coordinates tall) to an OpenGL coordinate (-1.0 - 1.0).
     pub fn coord(x: i32) -> f32 {
-        // (x as f32 - 500.0) / 1000.0
-        x as f32 / 200.0
+        coordf(x as f32)
+    }
+
+    /// Like [`coord`], for a fractional coordinate.
+    pub fn coordf(x: f32) -> f32 {
+        x / (super::screen_height() as f32 / 2.0)
     }
 }
 
 /// Conversions from glutin/glium units to genji units.
 pub(crate) mod gl2gj {
-    /// Converts a pixel coordinate to a genji coordinate.
-    pub fn pxcoord(x: f64, dim: u32) -> i32 {
-        ((x / dim as f64 - 0.5 * x.signum()) * 400.0).ceil() as i32
+    /// Converts a pixel coordinate to a genji coordinate, on
+    /// a screen `dim` pixels / `span` coordinates across.
+    pub fn pxcoord(x: f64, dim: u32, span: u32) -> i32 {
+        ((x / dim as f64 - 0.5 * x.signum()) * span as f64).ceil() as i32
     }
 }
 
+/// Finds where a `design` resolution, scaled to fit (keeping
+/// its aspect ratio), sits centered in a `window` (both in
+/// pixels). Returns `(left, bottom, width, height)`.
+pub(crate) fn letterbox(window: (u32, u32), design: (u32, u32)) -> (u32, u32, u32, u32) {
+    let scale = (window.0 as f32 / design.0 as f32).min(window.1 as f32 / design.1 as f32);
+    let w = ((design.0 as f32 * scale).round() as u32).clamp(1, window.0.max(1));
+    let h = ((design.1 as f32 * scale).round() as u32).clamp(1, window.1.max(1));
+
+    (
+        window.0.saturating_sub(w) / 2,
+        window.1.saturating_sub(h) / 2,
+        w,
+        h,
+    )
+}
+
+/// Finds where a `design` resolution, scaled up by the largest
+/// integer that fits, sits centered in a `window` (both in
+/// pixels). Returns `(left, bottom, width, height)`.
+pub(crate) fn pixel_viewport(window: (u32, u32), design: (u32, u32)) -> (u32, u32, u32, u32) {
+    let scale = (window.0 / design.0).min(window.1 / design.1).max(1);
+    let (w, h) = (design.0 * scale, design.1 * scale);
+
+    (
+        window.0.saturating_sub(w) / 2,
+        window.1.saturating_sub(h) / 2,
+        w,
+        h,
+    )
+}
+
 /// Helpers for creating and handling matrices.
 /// Currently unused.
 pub(crate) mod matrix {
@@ -110,18 +194,39 @@ pub(crate) mod matrix {
 //     sprites
 // }
 
-/// Hashes any type byte-by-byte.
-///
-/// The &str "abc" and the &[u8] [61, 62, 63] result in the same hash.
-/// Currently unused.
-pub(crate) fn hash<T>(data: &T) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    let bytes: &[u8] = unsafe {
-        core::slice::from_raw_parts((data as *const T) as *const u8, ::core::mem::size_of::<T>())
-    };
-    for byte in bytes {
-        byte.hash(&mut hasher);
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+
+    use super::{report_error, set_error_handler};
+
+    static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn record(message: &str) {
+        MESSAGES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(message.to_string());
     }
 
-    hasher.finish()
+    /// Sends reported errors to a list (for every test, since the
+    /// handler is global), and returns the ones so far containing
+    /// `pattern`.
+    pub(crate) fn reported(pattern: &str) -> Vec<String> {
+        set_error_handler(record);
+        MESSAGES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|message| message.contains(pattern))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn errors_go_to_the_handler() {
+        reported("");
+        report_error("something went wrong");
+        assert_eq!(reported("something went wrong"), ["something went wrong"]);
+    }
 }
@@ -0,0 +1,115 @@
+//! Reloads file-backed assets when they change on disk.
+//!
+//! Only available with the `hot-reload` feature.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    ecs::World,
+    graphics::sprite::{self, Text, Texture},
+    helpers,
+};
+
+/// Watches the files behind [`Texture`]s and [`Text`]s and
+/// swaps in the new data when they're modified.
+pub(crate) struct HotReload {
+    watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<Event>>,
+
+    /// The directories being watched. Directories are watched
+    /// instead of files, since many editors replace a file
+    /// rather than writing to it.
+    watched: HashSet<PathBuf>,
+}
+
+impl HotReload {
+    pub fn new() -> Option<Self> {
+        let (tx, rx) = channel();
+        let watcher = notify::recommended_watcher(tx)
+            .map_err(|e| helpers::report_error(&format!("failed to start asset watcher: {e}")))
+            .ok()?;
+
+        Some(Self {
+            watcher,
+            rx,
+            watched: HashSet::new(),
+        })
+    }
+
+    fn watch(&mut self, path: &Path) {
+        let Some(dir) = path.parent() else {
+            return;
+        };
+
+        if !self.watched.contains(dir) {
+            self.watched.insert(dir.to_path_buf());
+            if let Err(e) = self.watcher.watch(dir, RecursiveMode::NonRecursive) {
+                helpers::report_error(&format!("failed to watch {}: {e}", dir.display()));
+            }
+        }
+    }
+
+    /// Starts watching any new assets, and reloads the ones that changed.
+    ///
+    /// Assets' paths are canonicalized when they're loaded,
+    /// so they can be compared with the changed files as-is.
+    pub fn update(&mut self, world: &mut World) {
+        for (_, texture) in world.query_mut::<&Texture>() {
+            if let Some(path) = &texture.path {
+                self.watch(path);
+            }
+        }
+        for (_, text) in world.query_mut::<&Text>() {
+            if let Some(path) = &text.font_path {
+                self.watch(path);
+            }
+        }
+
+        let mut changed = HashSet::new();
+        for ev in self.rx.try_iter().flatten() {
+            if matches!(ev.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                changed.extend(ev.paths.into_iter().filter_map(|p| p.canonicalize().ok()));
+            }
+        }
+
+        if changed.is_empty() {
+            return;
+        }
+
+        for (_, texture) in world.query_mut::<&mut Texture>() {
+            let Some(path) = texture.path.as_ref().filter(|p| changed.contains(*p)) else {
+                continue;
+            };
+
+            if let Some(data) = sprite::load_image_file(path, None) {
+                // Keep the texture's scale, e.g. the default
+                // of one coordinate per pixel.
+                let (old_w, old_h) = texture.dimensions;
+                let (new_w, new_h) = data.dimensions();
+                if old_w > 0 && old_h > 0 {
+                    texture.w = (texture.w as i64 * new_w as i64 / old_w as i64) as i32;
+                    texture.h = (texture.h as i64 * new_h as i64 / old_h as i64) as i32;
+                }
+
+                texture.dimensions = (new_w, new_h);
+                texture.data = data.into_raw().into();
+            }
+        }
+
+        for (_, text) in world.query_mut::<&mut Text>() {
+            let Some(path) = text.font_path.as_ref().filter(|p| changed.contains(*p)) else {
+                continue;
+            };
+
+            if let Some(font) = sprite::load_font_file(path) {
+                text.font = font;
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::{Key, Keys, KEYS_NUM};
+
+/// Remembers when each key was last pressed, so that a press
+/// made slightly too early (e.g. jumping just before landing)
+/// still counts. Driven by `GameState.delta` (in milliseconds).
+///
+/// ```
+/// # use genji::input::{InputBuffer, Key, Keys};
+/// let mut buffer = InputBuffer::new();
+///
+/// let mut pressed = Keys::new();
+/// pressed[Key::Space] = true;
+/// buffer.update(&pressed, 16);
+///
+/// // A few frames later...
+/// buffer.update(&Keys::new(), 50);
+/// assert!(buffer.was_pressed_within(Key::Space, 100));
+/// assert!(buffer.consume(Key::Space, 100));
+/// assert!(!buffer.was_pressed_within(Key::Space, 100));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputBuffer {
+    now: u128,
+    last_pressed: [Option<u128>; KEYS_NUM],
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by `delta` milliseconds, then records
+    /// every key in `pressed` (i.e. `GameState.pressed`) as
+    /// pressed just now. Call this once per frame.
+    pub fn update(&mut self, pressed: &Keys, delta: u128) {
+        self.now += delta;
+
+        for key in Key::ALL {
+            if pressed[key] {
+                self.last_pressed[key as usize] = Some(self.now);
+            }
+        }
+    }
+
+    /// Returns whether or not `key` was pressed in the
+    /// last `ms` milliseconds (including this frame).
+    pub fn was_pressed_within(&self, key: Key, ms: u128) -> bool {
+        self.last_pressed[key as usize].is_some_and(|at| self.now - at <= ms)
+    }
+
+    /// Like [`was_pressed_within`](Self::was_pressed_within), but
+    /// forgets the press if it counts, so it only triggers once.
+    pub fn consume(&mut self, key: Key, ms: u128) -> bool {
+        let pressed = self.was_pressed_within(key, ms);
+        if pressed {
+            self.last_pressed[key as usize] = None;
+        }
+
+        pressed
+    }
+
+    /// Forgets every press.
+    pub fn clear(&mut self) {
+        self.last_pressed = [None; KEYS_NUM];
+    }
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self {
+            now: 0,
+            last_pressed: [None; KEYS_NUM],
+        }
+    }
+}
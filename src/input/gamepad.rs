@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+
+use super::{Key, Keys};
+
+/// A button on a gamepad, named after its position
+/// on a standard (Xbox-style) controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadButton {
+    /// The bottom face button (A on Xbox, Cross on PlayStation).
+    South,
+    /// The right face button (B on Xbox, Circle on PlayStation).
+    East,
+    /// The top face button (Y on Xbox, Triangle on PlayStation).
+    North,
+    /// The left face button (X on Xbox, Square on PlayStation).
+    West,
+
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+
+    Select,
+    Start,
+    Mode,
+
+    /// Pressing in the left stick.
+    LeftStick,
+    /// Pressing in the right stick.
+    RightStick,
+
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// An analog axis on a gamepad.
+///
+/// Stick axes range from `-1.0` (left, down) to `1.0`
+/// (right, up), and trigger axes from `0.0` to `1.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(usize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+const AXES_NUM: usize = 6;
+
+/// Which of a pair of gamepad inputs, e.g. for
+/// [`Gamepad::trigger`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadSide {
+    Left,
+    Right,
+}
+
+/// A gamepad input that can be mapped onto a [`Key`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadInput {
+    Button(GamepadButton),
+    /// An axis pushed past the deadzone towards `1.0`.
+    AxisPositive(GamepadAxis),
+    /// An axis pushed past the deadzone towards `-1.0`.
+    AxisNegative(GamepadAxis),
+}
+
+/// The state of a gamepad.
+///
+/// Genji doesn't read gamepads itself; update this
+/// from a library such as `gilrs` (e.g. in `onloop`),
+/// and genji will map it onto the keys each frame
+/// (see [`GamepadMap`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gamepad {
+    buttons: HashSet<GamepadButton>,
+    axes: [f32; AXES_NUM],
+    /// How far (`0.0`-`1.0`) a stick must be pushed, in any
+    /// direction, before [`Gamepad::left_stick`] and
+    /// [`Gamepad::right_stick`] read anything but zero.
+    ///
+    /// Defaults to `0.15`.
+    pub stick_deadzone: f32,
+    /// How far (`0.0`-`1.0`) a trigger must be pulled before
+    /// [`Gamepad::trigger`] reads anything but zero.
+    ///
+    /// Defaults to `0.05`.
+    pub trigger_deadzone: f32,
+}
+
+impl Gamepad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether or not a button is held.
+    pub fn set_button(&mut self, button: GamepadButton, pressed: bool) {
+        if pressed {
+            self.buttons.insert(button);
+        } else {
+            self.buttons.remove(&button);
+        }
+    }
+
+    /// Returns whether or not a button is held.
+    pub fn button(&self, button: GamepadButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// Sets the value of an axis, clamped to `-1.0..=1.0`.
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes[axis as usize] = value.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the value of an axis.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    /// Returns the position of the left stick, with
+    /// `stick_deadzone` applied. See [`Gamepad::stick`].
+    pub fn left_stick(&self) -> (f32, f32) {
+        self.stick(GamepadSide::Left)
+    }
+
+    /// Returns the position of the right stick, with
+    /// `stick_deadzone` applied. See [`Gamepad::stick`].
+    pub fn right_stick(&self) -> (f32, f32) {
+        self.stick(GamepadSide::Right)
+    }
+
+    /// Returns the position of a stick, each axis from `-1.0`
+    /// to `1.0`, with `stick_deadzone` applied.
+    ///
+    /// The deadzone is radial: a stick resting anywhere within
+    /// it reads exactly `(0.0, 0.0)`, and past it the distance
+    /// is rescaled to start from zero, without changing the
+    /// direction. Unlike a deadzone per axis, this doesn't snap
+    /// slightly diagonal movement onto the axes.
+    pub fn stick(&self, side: GamepadSide) -> (f32, f32) {
+        let (x, y) = match side {
+            GamepadSide::Left => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            GamepadSide::Right => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        };
+        let (x, y) = (self.axis(x), self.axis(y));
+
+        let len = x.hypot(y);
+        let deadzone = self.stick_deadzone.clamp(0.0, 1.0);
+        if len <= deadzone {
+            return (0.0, 0.0);
+        }
+
+        let scaled = ((len - deadzone) / (1.0 - deadzone)).min(1.0);
+        (x / len * scaled, y / len * scaled)
+    }
+
+    /// Returns how far a trigger is pulled, from `0.0` to `1.0`,
+    /// with `trigger_deadzone` applied (and rescaled past it).
+    pub fn trigger(&self, side: GamepadSide) -> f32 {
+        let axis = match side {
+            GamepadSide::Left => GamepadAxis::LeftTrigger,
+            GamepadSide::Right => GamepadAxis::RightTrigger,
+        };
+        let value = self.axis(axis);
+
+        let deadzone = self.trigger_deadzone.clamp(0.0, 1.0);
+        if value <= deadzone {
+            return 0.0;
+        }
+
+        ((value - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Self {
+            buttons: HashSet::new(),
+            axes: [0.0; AXES_NUM],
+            stick_deadzone: 0.15,
+            trigger_deadzone: 0.05,
+        }
+    }
+}
+
+/// Maps gamepad inputs onto [`Key`]s, so that keyboard
+/// controls work with a controller unchanged.
+///
+/// Several inputs can map to the same key, and one input
+/// can map to several keys.
+///
+/// ```
+/// # use genji::input::{GamepadAxis, GamepadButton, GamepadInput, GamepadMap, Key};
+/// let map = GamepadMap::new()
+///     .bind(GamepadInput::Button(GamepadButton::South), Key::Space)
+///     .bind(GamepadInput::AxisPositive(GamepadAxis::LeftStickY), Key::Up)
+///     .bind(GamepadInput::AxisNegative(GamepadAxis::LeftStickY), Key::Down);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadMap {
+    bindings: Vec<(GamepadInput, Key)>,
+    /// How far (`0.0`-`1.0`) an axis must be pushed
+    /// before it counts as pressing a key.
+    ///
+    /// Defaults to `0.5`.
+    pub deadzone: f32,
+}
+
+impl GamepadMap {
+    /// Creates a map with no bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the D-pad and left stick to the arrow keys, the
+    /// bottom face button to `Space`, and start to `Esc`.
+    pub fn arrows() -> Self {
+        use GamepadAxis::*;
+        use GamepadButton::*;
+        use GamepadInput::*;
+
+        Self::new()
+            .bind(Button(DPadUp), Key::Up)
+            .bind(Button(DPadDown), Key::Down)
+            .bind(Button(DPadLeft), Key::Left)
+            .bind(Button(DPadRight), Key::Right)
+            .bind(AxisPositive(LeftStickY), Key::Up)
+            .bind(AxisNegative(LeftStickY), Key::Down)
+            .bind(AxisNegative(LeftStickX), Key::Left)
+            .bind(AxisPositive(LeftStickX), Key::Right)
+            .bind(Button(South), Key::Space)
+            .bind(Button(Start), Key::Esc)
+    }
+
+    /// Adds a binding from `input` to `key`.
+    pub fn bind(mut self, input: GamepadInput, key: Key) -> Self {
+        self.bindings.push((input, key));
+        self
+    }
+
+    /// Removes every binding from `input`.
+    pub fn unbind(&mut self, input: GamepadInput) {
+        self.bindings.retain(|&(bound, _)| bound != input);
+    }
+
+    /// Returns the keys held by `gamepad` under this map.
+    pub fn keys(&self, gamepad: &Gamepad) -> Keys {
+        let mut keys = Keys::new();
+        for &(input, key) in &self.bindings {
+            let held = match input {
+                GamepadInput::Button(button) => gamepad.button(button),
+                GamepadInput::AxisPositive(axis) => gamepad.axis(axis) > self.deadzone,
+                GamepadInput::AxisNegative(axis) => gamepad.axis(axis) < -self.deadzone,
+            };
+
+            keys[key] |= held;
+        }
+
+        keys
+    }
+}
+
+impl Default for GamepadMap {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            deadzone: 0.5,
+        }
+    }
+}
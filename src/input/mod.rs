@@ -17,12 +17,37 @@
 //! // You press the spacebar...
 //! assert!(keys[Key::Space]);
 //! ```
+//!
+//! Gamepads can be mapped onto the same keys via
+//! [`GamepadMap`].
+//!
+//! For more forgiving controls, [`InputBuffer`] remembers
+//! presses for a short while.
+//!
+//! Other threads can send events into genji via an [`EventProxy`].
 
 use std::ops::{Add, Index, IndexMut, Sub};
 
 use glium::glutin::event::VirtualKeyCode;
 
-const KEYS_NUM: usize = 87;
+mod buffer;
+mod gamepad;
+mod proxy;
+pub use buffer::InputBuffer;
+pub use gamepad::{Gamepad, GamepadAxis, GamepadButton, GamepadInput, GamepadMap, GamepadSide};
+pub use proxy::{EventProxy, UserEvent};
+
+/// The icon shown for the mouse cursor, borrowed
+/// from [`glutin`](glium::glutin).
+/// Set via [`GameState::cursor_icon`](crate::state::GameState::cursor_icon).
+pub use glium::glutin::window::CursorIcon;
+
+/// The raw window and input events, borrowed from
+/// [`glutin`](glium::glutin). These are passed to the
+/// optional `on_event` function; see [`genji::init`](crate::init).
+pub use glium::glutin::event;
+
+const KEYS_NUM: usize = 84;
 
 /// A set of keys. Get a keys state with `keys[key]`.
 ///
@@ -45,6 +70,42 @@ impl Keys {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns whether or not every key in `keys` is down.
+    ///
+    /// ```
+    /// # use genji::input::{Key, Keys};
+    /// # let keys = Keys::new();
+    /// if keys.all_down(&[Key::Ctrl, Key::S]) {
+    ///     // save...
+    /// }
+    /// ```
+    pub fn all_down(&self, keys: &[Key]) -> bool {
+        keys.iter().all(|&key| self[key])
+    }
+
+    /// Returns whether or not any key in `keys` is down.
+    pub fn any_down(&self, keys: &[Key]) -> bool {
+        keys.iter().any(|&key| self[key])
+    }
+
+    /// Updates the state of the keys produced by a single
+    /// virtual key (see [`Key::from_virtual`]).
+    ///
+    /// All but the last key are synthetic modifiers (e.g. `Shift`
+    /// for `*`). On release, those are reset to their state in
+    /// `modifiers` (the real modifier state), so that releasing `*`
+    /// doesn't release a Shift that's still being held.
+    pub fn set_virtual(&mut self, keys: &[Key], pressed: bool, modifiers: &Keys) {
+        let Some((&key, synthetic)) = keys.split_last() else {
+            return;
+        };
+
+        self[key] = pressed;
+        for &modifier in synthetic {
+            self[modifier] = pressed || modifiers[modifier];
+        }
+    }
 }
 
 impl Default for Keys {
@@ -81,7 +142,7 @@ impl<T: Into<usize>> IndexMut<T> for Keys {
     }
 }
 
-/// A key. Corresponds to a number (0-86).
+/// A key. Corresponds to a number (0-83).
 ///
 /// ```
 /// # use genji::input::{Key, Keys};
@@ -190,6 +251,94 @@ pub enum Key {
 }
 
 impl Key {
+    /// Every key, in order, such that `Key::ALL[key as usize] == key`.
+    pub const ALL: [Key; KEYS_NUM] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+        Key::Zero,
+        Key::One,
+        Key::Two,
+        Key::Three,
+        Key::Four,
+        Key::Five,
+        Key::Six,
+        Key::Seven,
+        Key::Eight,
+        Key::Nine,
+        Key::Up,
+        Key::Left,
+        Key::Down,
+        Key::Right,
+        Key::Tab,
+        Key::Shift,
+        Key::RShift,
+        Key::Caps,
+        Key::Space,
+        Key::Esc,
+        Key::Ctrl,
+        Key::RCtrl,
+        Key::Alt,
+        Key::RAlt,
+        Key::Super,
+        Key::RSuper,
+        Key::Backspace,
+        Key::Enter,
+        Key::Backtick,
+        Key::Minus,
+        Key::Equals,
+        Key::Backslash,
+        Key::LBracket,
+        Key::RBracket,
+        Key::Semicolon,
+        Key::Apostrophe,
+        Key::Comma,
+        Key::Period,
+        Key::Slash,
+        Key::F1,
+        Key::F2,
+        Key::F3,
+        Key::F4,
+        Key::F5,
+        Key::F6,
+        Key::F7,
+        Key::F8,
+        Key::F9,
+        Key::F10,
+        Key::F11,
+        Key::F12,
+        Key::LClick,
+        Key::RClick,
+        Key::MClick,
+        Key::M1,
+        Key::M2,
+        Key::M3,
+        Key::M4,
+    ];
+
     pub const fn from_keycode(code: u32) -> Option<Self> {
         Some(match code {
             30 => Key::A,
@@ -381,12 +530,17 @@ impl Key {
     }
 }
 
+/// Converts an index into a key (see [`Key::ALL`]).
+///
+/// Panics if the index is out of range (84 or higher).
 impl<T> From<T> for Key
 where
     usize: From<T>,
 {
     fn from(value: T) -> Self {
-        usize::from(value).into()
+        let index = usize::from(value);
+        assert!(index < KEYS_NUM, "key index {index} out of range");
+        Key::ALL[index]
     }
 }
 
@@ -0,0 +1,47 @@
+use std::any::Any;
+
+use glium::glutin::event_loop::EventLoopProxy;
+
+/// A custom event sent through an [`EventProxy`]. Arrives at
+/// `on_event` as [`Event::UserEvent`](super::event::Event::UserEvent);
+/// use [`downcast_ref`](Box::downcast_ref) to get your event back out.
+pub type UserEvent = Box<dyn Any + Send>;
+
+/// Sends custom events into genji's event loop, e.g. from a
+/// networking thread. Get one via
+/// [`GameState::event_proxy`](crate::state::GameState::event_proxy).
+///
+/// ```ignore
+/// # use genji::{prelude::*, input::event::Event};
+/// struct Message(String);
+///
+/// // In onloop...
+/// let proxy = state.event_proxy().unwrap();
+/// std::thread::spawn(move || {
+///     proxy.send(Message("hello".to_string()));
+/// });
+///
+/// fn on_event(state: &mut State, world: &mut World, event: &Event<UserEvent>) -> bool {
+///     if let Event::UserEvent(event) = event {
+///         if let Some(Message(message)) = event.downcast_ref() {
+///             println!("{message}");
+///         }
+///     }
+///
+///     false
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventProxy(EventLoopProxy<UserEvent>);
+
+impl EventProxy {
+    pub(crate) fn new(proxy: EventLoopProxy<UserEvent>) -> Self {
+        Self(proxy)
+    }
+
+    /// Sends an event to `on_event`. Returns false if genji
+    /// has already closed, in which case the event is dropped.
+    pub fn send<E: Any + Send>(&self, event: E) -> bool {
+        self.0.send_event(Box::new(event)).is_ok()
+    }
+}
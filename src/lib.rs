@@ -2,65 +2,470 @@
 
 // TODO: turn old doctests into proper tests
 
+use std::collections::HashSet;
 use std::thread;
 use std::time::{Duration, Instant};
 
 use audio::Audio;
 pub use genji_macros::init;
+pub use helpers::set_error_handler;
 
 pub mod audio;
 pub mod ecs;
 pub mod graphics;
 pub mod input;
+pub mod loading;
 pub mod prelude;
+pub mod scene;
 pub mod shape;
 pub mod state;
 pub mod store;
+pub mod ui;
+pub mod util;
 
 use input::{Key, Keys};
 
-use ecs::World;
+use ecs::{Order, World};
 use glium::{glutin, Surface};
 use graphics::{
     sprite::{Sprite, SpriteData, Text, Texture},
-    Angle, Color, Depth, Fill, StrokeWeight,
+    AlwaysOnTop, Angle, Color, Depth, Fill, Parent, Stroke, StrokeCap, StrokeJoin, StrokeWeight,
+    Viewport,
 };
-use shape::{Circle, Point, Rect, Triangle};
-use state::GameState;
+use shape::{Circle, FPoint, Point, Rect, Triangle};
+use state::{GameState, RenderStats};
 
 mod helpers;
 use helpers::gl2gj;
 
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+
+/// How long a button must be held to count as a long press.
+const LONG_PRESS: Duration = Duration::from_millis(500);
+
+/// The longest time between two clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(300);
+
+/// How far (in coordinates) the mouse can move before a press
+/// stops counting as a long press or double click.
+const GESTURE_SLOP: i32 = 4;
+
+/// How far (in coordinates) one line of scrolling moves,
+/// before `GameState.scroll_sensitivity`.
+const SCROLL_LINE: f32 = 40.0;
+
+fn within_slop(delta: Point) -> bool {
+    delta.0.abs().max(delta.1.abs()) <= GESTURE_SLOP
+}
+
+/// Where the scene is drawn when it doesn't fill the window
+/// (with `pixel_perfect` or `virtual_size`), as the size to
+/// render at and the `(left, bottom, width, height)` to scale
+/// it into, in pixels.
+type Letterbox = ((u32, u32), (u32, u32, u32, u32));
+
+fn letterbox<T>(state: &GameState<T>, window: (u32, u32)) -> Option<Letterbox> {
+    let valid = |&(w, h): &(u32, u32)| w > 0 && h > 0;
+
+    if let Some(design) = state.pixel_perfect.filter(valid) {
+        Some((design, helpers::pixel_viewport(window, design)))
+    } else {
+        let region = helpers::letterbox(window, state.virtual_size.filter(valid)?);
+        Some(((region.2, region.3), region))
+    }
+}
+
+/// Reads the pixels watched by `state.pixel_at` from the
+/// finished frame (`window` pixels large), forgetting the
+/// ones that weren't asked for this frame.
+fn read_pixels<T>(
+    state: &mut GameState<T>,
+    target: &glium::Frame,
+    display: &glium::Display,
+    window: (u32, u32),
+) {
+    state.pixels.retain(|&(_, _, asked)| asked);
+    if state.pixels.is_empty() {
+        return;
+    }
+
+    let (left, bottom, w, h) =
+        letterbox(state, window).map_or((0, 0, window.0, window.1), |(_, region)| region);
+    let (span_x, span_y) = state
+        .virtual_size
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or((helpers::SCREEN_HEIGHT, helpers::SCREEN_HEIGHT));
+
+    let pixel = glium::Texture2d::empty(display, 1, 1).expect("failed to read pixel");
+    for (point, color, asked) in &mut state.pixels {
+        *asked = false;
+
+        // The inverse of how the mouse is converted to coordinates.
+        let x = (point.0 as f32 / span_x as f32 + 0.5) * w as f32;
+        let y = (point.1 as f32 / span_y as f32 + 0.5) * h as f32;
+        if !(0.0..w as f32).contains(&x) || !(0.0..h as f32).contains(&y) {
+            *color = None;
+            continue;
+        }
+
+        target.blit_color(
+            &glium::Rect {
+                left: left + x as u32,
+                bottom: bottom + y as u32,
+                width: 1,
+                height: 1,
+            },
+            &pixel.as_surface(),
+            &glium::BlitTarget {
+                left: 0,
+                bottom: 0,
+                width: 1,
+                height: 1,
+            },
+            glium::uniforms::MagnifySamplerFilter::Nearest,
+        );
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = pixel.read();
+        *color = data
+            .first()
+            .and_then(|row| row.first())
+            .map(|&(r, g, b, a)| Color::new(r, g, b, a));
+    }
+}
+
+/// Clears `target` to `clear` (if any), then draws the
+/// (already sorted) sprites to it, counting them in `stats`.
+fn draw_scene<S: Surface>(
+    target: &mut S,
+    clear: Option<Color>,
+    sprites: &[(Sprite, SpriteData)],
+    display: &glium::Display,
+    shaders: &graphics::shaders::Shaders,
+    stats: &mut RenderStats,
+) {
+    if let Some(col) = clear {
+        let col = col.to_f32();
+        target.clear_color_and_depth((col[0], col[1], col[2], col[3]), 1.0);
+    }
+
+    for (sprite, ex) in sprites {
+        let (draw_calls, vertices) = sprite.draw(target, *ex, display, shaders);
+        stats.draw_calls += draw_calls;
+        stats.vertices += vertices;
+        stats.sprites_drawn += 1;
+    }
+}
+
+/// An offscreen target, e.g. for `pixel_perfect`.
+type Canvas = (glium::Texture2d, glium::framebuffer::DepthRenderBuffer);
+
+/// Reuses `cached` if it's the right size, or creates a new canvas.
+/// Also returns whether the canvas is new, since a new canvas
+/// starts out as garbage and has to be cleared.
+fn canvas(display: &glium::Display, cached: Option<Canvas>, (w, h): (u32, u32)) -> (Canvas, bool) {
+    match cached {
+        Some((color, depth)) if color.dimensions() == (w, h) => ((color, depth), false),
+        _ => {
+            let color =
+                glium::Texture2d::empty(display, w, h).expect("failed to create offscreen canvas");
+            let depth = glium::framebuffer::DepthRenderBuffer::new(
+                display,
+                glium::texture::DepthFormat::I24,
+                w,
+                h,
+            )
+            .expect("failed to create offscreen canvas");
+            ((color, depth), true)
+        }
+    }
+}
+
+/// Draws the scene to `target` (of `size` pixels), once per
+/// viewport if there are any, then draws `overlay` over it all.
+#[allow(clippy::too_many_arguments)]
+fn draw_frame<S: Surface>(
+    target: &mut S,
+    size: (u32, u32),
+    clear: Option<Color>,
+    sprites: &[(Sprite, SpriteData)],
+    overlay: &[(Sprite, SpriteData)],
+    viewports: &[Viewport],
+    canvases: &mut Vec<Canvas>,
+    display: &glium::Display,
+    shaders: &graphics::shaders::Shaders,
+    stats: &mut RenderStats,
+) {
+    if viewports.is_empty() {
+        canvases.clear();
+        draw_scene(target, clear, sprites, display, shaders, stats);
+        draw_scene(target, None, overlay, display, shaders, stats);
+        return;
+    }
+
+    // Clears the gaps between viewports.
+    if let Some(col) = clear {
+        let col = col.to_f32();
+        target.clear_color_and_depth((col[0], col[1], col[2], col[3]), 1.0);
+    }
+
+    // Each viewport is drawn to its own canvas, so that
+    // sprites are sized (and clipped) to its region.
+    let mut cached = std::mem::take(canvases).into_iter();
+    for viewport in viewports {
+        let (left, bottom, w, h) = viewport.pixels(size);
+        let ((color, depth), fresh) = canvas(display, cached.next(), (w.max(1), h.max(1)));
+        let clear = viewport
+            .clear_color
+            .or(clear)
+            .or(fresh.then(|| Color::new(0, 0, 0, 255)));
+
+        let offset = viewport.camera.offset();
+        let offset: Vec<_> = sprites
+            .iter()
+            .map(|&(sprite, mut ex)| {
+                ex.x += offset.0;
+                ex.y += offset.1;
+                (sprite, ex)
+            })
+            .collect();
+
+        {
+            let mut surface =
+                glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(display, &color, &depth)
+                    .expect("failed to create offscreen canvas");
+
+            draw_scene(&mut surface, clear, &offset, display, shaders, stats);
+
+            surface.blit_color(
+                &glium::Rect {
+                    left: 0,
+                    bottom: 0,
+                    width: w,
+                    height: h,
+                },
+                target,
+                &glium::BlitTarget {
+                    left,
+                    bottom,
+                    width: w as i32,
+                    height: h as i32,
+                },
+                glium::uniforms::MagnifySamplerFilter::Nearest,
+            );
+            stats.draw_calls += 1;
+        }
+
+        canvases.push((color, depth));
+    }
+
+    draw_scene(target, None, overlay, display, shaders, stats);
+}
+
+/// The optional components of a sprite, fetched in the same
+/// query as the sprite itself rather than looked up one by one.
+type SpriteComponents<'a> = (
+    Option<&'a Angle>,
+    Option<&'a Color>,
+    Option<&'a Depth>,
+    Option<&'a Fill>,
+    Option<&'a StrokeWeight>,
+    Option<&'a StrokeJoin>,
+    Option<&'a StrokeCap>,
+    Option<&'a Stroke>,
+    Option<&'a Order>,
+    Option<&'a AlwaysOnTop>,
+    Option<&'a Parent>,
+);
+
+/// The distance, in coordinates, between the stats
+/// overlay and the edges of the screen.
+const STATS_MARGIN: f32 = 8.0;
+
+/// Creates the text for `state.show_stats`, placed in the
+/// top-left corner of the screen.
+fn stats_overlay<T>(state: &GameState<T>, entities: u32) -> Option<(Text, SpriteData)> {
+    let font = state.stats_font.clone().filter(|_| state.show_stats)?;
+
+    let text = Text {
+        text: format!(
+            "fps: {}\nframe: {}ms\nentities: {}\ndraw calls: {}",
+            1000 / state.delta.max(1),
+            state.delta,
+            entities,
+            state.render_stats.draw_calls,
+        ),
+        font,
+        font_size: 24.0,
+        font_path: None,
+        quality: 1.0,
+        background: None,
+        max_width: None,
+    };
+
+    // Text is drawn at half a coordinate per pixel.
+    let (w, h) =
+        graphics::text::measure_exact(&text.font, text.font_size, f32::INFINITY, &text.text);
+    let scale = state.ui_scale * 0.5;
+    let (w, h) = (w * scale, h * scale);
+
+    let (fw, fh) = state.framebuffer;
+    let half_h = helpers::screen_height() as f32 / 2.0;
+    let half_w = half_h * fw as f32 / fh.max(1) as f32;
+
+    let ex = SpriteData {
+        text_scale: state.ui_scale,
+        ..SpriteData::new().pos(Point(
+            (-half_w + STATS_MARGIN + w / 2.0).round() as i32,
+            (half_h - STATS_MARGIN - h / 2.0).round() as i32,
+        ))
+    };
+
+    Some((text, ex))
+}
+
+/// The size closest to `size` with the aspect ratio `aspect`,
+/// keeping either the width or the height.
+fn locked_size((w, h): (u32, u32), (aw, ah): (u32, u32)) -> (u32, u32) {
+    if aw == 0 || ah == 0 {
+        return (w, h);
+    }
+
+    let by_width = (w, (w as f64 * ah as f64 / aw as f64).round() as u32);
+    let by_height = ((h as f64 * aw as f64 / ah as f64).round() as u32, h);
+
+    if by_width.1.abs_diff(h) <= by_height.0.abs_diff(w) {
+        by_width
+    } else {
+        by_height
+    }
+}
+
+/// A user function that sees every event before genji does,
+/// returning true if genji shouldn't handle it.
+#[doc(hidden)]
+pub type EventHandler<T> =
+    fn(&mut GameState<T>, &mut World, &glutin::event::Event<'_, input::UserEvent>) -> bool;
+
+/// A user function run after every frame is presented.
+#[doc(hidden)]
+pub type PostRender<T> = fn(&mut GameState<T>);
+
 /// Runs the engine code for genji. Automatically run
 /// via `genji::init`, so please don't do this manually.
 #[doc(hidden)]
 pub fn main<T: 'static>(
     init: fn() -> (GameState<T>, World),
     onloop: fn(&mut GameState<T>, &mut World, &mut Audio) -> bool,
-    close: fn(GameState<T>, World),
+    close: fn(GameState<T>, World, &mut Audio),
+    on_event: Option<EventHandler<T>>,
+    post_render: Option<PostRender<T>>,
+    loading: Option<loading::Loading<T>>,
 ) {
-    let (state, world) = init();
-
-    let event_loop = glutin::event_loop::EventLoop::new();
-    let wb = glutin::window::WindowBuilder::new()
+    let start_time = Instant::now();
+
+    // Loading starts before anything else, so
+    // it runs alongside opening the window.
+    let mut loader = loading.map(loading::Loading::start);
+    let (mut state, world) = init();
+    state.start_time = start_time;
+
+    let event_loop =
+        glutin::event_loop::EventLoopBuilder::<input::UserEvent>::with_user_event().build();
+    state.event_proxy = Some(input::EventProxy::new(event_loop.create_proxy()));
+    let mut wb = glutin::window::WindowBuilder::new()
         .with_inner_size(glutin::dpi::LogicalSize::new(state.width, state.height))
-        .with_title(&state.title);
+        .with_title(&state.title)
+        // Shown once the first frame is ready, to avoid a blank flash.
+        .with_visible(false)
+        .with_transparent(state.transparent);
+
+    if let Some((w, h)) = state.min_size {
+        wb = wb.with_min_inner_size(glutin::dpi::LogicalSize::new(w, h));
+    }
+
+    if let Some((w, h)) = state.max_size {
+        wb = wb.with_max_inner_size(glutin::dpi::LogicalSize::new(w, h));
+    }
+
+    wb = wb.with_fullscreen(state.window_mode.fullscreen());
 
     let cb = glutin::ContextBuilder::new();
     let display = glium::Display::new(wb, cb, &event_loop).expect("genji failed to make a display");
 
     let shaders = graphics::shaders::Shaders::new(&display);
+    state.graphics_info = graphics::GraphicsInfo::query(&display);
+
+    let monitor = display.gl_window().window().current_monitor();
+    state.refresh_rate = monitor
+        .as_ref()
+        .and_then(|monitor| monitor.refresh_rate_millihertz())
+        .filter(|&mhz| mhz > 0);
+    state.video_modes = monitor.map_or_else(Vec::new, |monitor| monitor.video_modes().collect());
+
+    if let (true, Some(mhz)) = (state.fps_is_default, state.refresh_rate) {
+        state.fps = (mhz as u128 + 500) / 1000;
+    }
 
     let mut last = Instant::now();
+    let mut cursor_icon = state.cursor_icon;
+    let mut window_mode = state.window_mode.clone();
+    let mut visible = false;
+    // The last size corrected for `lock_aspect`, and what it was corrected to.
+    let mut aspect_fix: Option<((u32, u32), (u32, u32))> = None;
+    let mut last_clear_color = state.clear_color;
+    let mut min_size = state.min_size;
+    let mut max_size = state.max_size;
+
+    // The offscreen target for `pixel_perfect` / `virtual_size`, kept between
+    // frames so it doesn't need to be recreated.
+    let mut canvas_cache: Option<Canvas> = None;
+    // The offscreen targets for each of `viewports`.
+    let mut viewport_canvases = Vec::new();
+
+    // The keys held by the gamepad last frame.
+    let mut gamepad_keys = Keys::new();
+
+    // Sprites that were already warned about missing a `Point`.
+    let mut unplaced = HashSet::new();
+
+    // The real state of the modifier keys, as opposed to
+    // the synthetic ones from e.g. `VirtualKeyCode::Asterisk`.
+    let mut modifiers = Keys::new();
+
+    // The mouse button that began the current drag, and when, as
+    // well as the last click, for recognizing gestures.
+    let mut press: Option<(Key, Instant)> = None;
+    let mut last_click: Option<(Key, Instant, Point)> = None;
+    display.gl_window().window().set_cursor_icon(cursor_icon);
 
     let mut state = Some(state);
     let mut world = Some(world);
     let mut audio = Audio::new();
+
+    #[cfg(feature = "hot-reload")]
+    let mut hot_reload = hot_reload::HotReload::new();
+
     event_loop.run(move |ev, _, control_flow| {
+        // Make sure `close` runs even if the loop is torn down
+        // without going through one of the usual exit paths.
+        if let glutin::event::Event::LoopDestroyed = ev {
+            if let (Some(state), Some(world)) = (state.take(), world.take()) {
+                close(state, world, &mut audio);
+            }
+            return;
+        }
+
         if state.is_none() || world.is_none() {
             // TODO: should genji panic/error on double-close?
             return;
         }
+
+        if let Some(on_event) = on_event {
+            if on_event(state.as_mut().unwrap(), world.as_mut().unwrap(), &ev) {
+                return;
+            }
+        }
         let state_ref = state.as_mut().unwrap();
         match ev {
             glutin::event::Event::WindowEvent { event, .. } => match event {
@@ -68,37 +473,54 @@ pub fn main<T: 'static>(
                     display.gl_window().resize(size);
                     state_ref.width = size.width;
                     state_ref.height = size.height;
+
+                    // Snap back to the locked aspect ratio, unless the OS
+                    // refused the last correction, to avoid a resize loop.
+                    let current = (size.width, size.height);
+                    let fix = aspect_fix.take();
+                    if let Some(aspect) = state_ref.lock_aspect {
+                        let locked = locked_size(current, aspect);
+                        if locked != current && fix.is_none_or(|(from, _)| from != current) {
+                            display
+                                .gl_window()
+                                .window()
+                                .set_inner_size(glutin::dpi::PhysicalSize::new(locked.0, locked.1));
+                            aspect_fix = Some((current, locked));
+                        }
+                    }
                 }
                 glutin::event::WindowEvent::CloseRequested => {
                     state_ref.asked_to_close = true;
                     if state_ref.close_on_request {
                         control_flow.set_exit();
-                        close(state.take().unwrap(), world.take().unwrap());
+                        close(state.take().unwrap(), world.take().unwrap(), &mut audio);
                     }
                 }
-                glutin::event::WindowEvent::ModifiersChanged(modifiers) => {
-                    state_ref.keys[Key::Alt] = modifiers.alt();
-                    state_ref.keys[Key::Ctrl] = modifiers.ctrl();
-                    state_ref.keys[Key::Shift] = modifiers.shift();
-                    state_ref.keys[Key::Super] = modifiers.logo();
-                    state_ref.pressed[Key::Alt] = modifiers.alt();
-                    state_ref.pressed[Key::Ctrl] = modifiers.ctrl();
-                    state_ref.pressed[Key::Shift] = modifiers.shift();
-                    state_ref.pressed[Key::Super] = modifiers.logo();
+                glutin::event::WindowEvent::ModifiersChanged(new_modifiers) => {
+                    modifiers[Key::Alt] = new_modifiers.alt();
+                    modifiers[Key::Ctrl] = new_modifiers.ctrl();
+                    modifiers[Key::Shift] = new_modifiers.shift();
+                    modifiers[Key::Super] = new_modifiers.logo();
+                    state_ref.keys[Key::Alt] = new_modifiers.alt();
+                    state_ref.keys[Key::Ctrl] = new_modifiers.ctrl();
+                    state_ref.keys[Key::Shift] = new_modifiers.shift();
+                    state_ref.keys[Key::Super] = new_modifiers.logo();
+                    state_ref.pressed[Key::Alt] = new_modifiers.alt();
+                    state_ref.pressed[Key::Ctrl] = new_modifiers.ctrl();
+                    state_ref.pressed[Key::Shift] = new_modifiers.shift();
+                    state_ref.pressed[Key::Super] = new_modifiers.logo();
                 }
                 glutin::event::WindowEvent::KeyboardInput { input, .. } => {
                     if let Some(ks) = Key::from_virtual(input.virtual_keycode) {
-                        for key in ks {
-                            match input.state {
-                                glutin::event::ElementState::Pressed => {
-                                    state_ref.keys[key] = true;
-                                    state_ref.pressed[key] = true;
-                                }
-                                glutin::event::ElementState::Released => {
-                                    state_ref.keys[key] = false;
-                                    state_ref.pressed[key] = false;
-                                }
+                        let pressed = input.state == glutin::event::ElementState::Pressed;
+                        state_ref.keys.set_virtual(&ks, pressed, &modifiers);
+
+                        if pressed {
+                            for key in ks {
+                                state_ref.pressed[key] = true;
                             }
+                        } else if let Some(&key) = ks.last() {
+                            state_ref.pressed[key] = false;
                         }
                     } else if let Some(key) = Key::from_keycode(input.scancode) {
                         match input.state {
@@ -108,14 +530,29 @@ pub fn main<T: 'static>(
                     }
                 }
                 glutin::event::WindowEvent::MouseWheel { delta, .. } => {
-                    state_ref.scroll = match delta {
+                    let (x, y) = match delta {
                         glutin::event::MouseScrollDelta::LineDelta(x, y) => {
-                            ((x + y) * 40.0).ceil() as i32
+                            ((x * SCROLL_LINE).ceil(), (y * SCROLL_LINE).ceil())
                         }
                         glutin::event::MouseScrollDelta::PixelDelta(s) => {
-                            gl2gj::pxcoord(s.x + s.y, state_ref.height)
+                            // `pxcoord` doesn't map zero to zero, since `signum(0.0)` is one.
+                            let px = |delta: f64| match delta {
+                                0.0 => 0.0,
+                                delta => gl2gj::pxcoord(delta, state_ref.height, helpers::screen_height()) as f32,
+                            };
+                            (px(s.x), px(s.y))
                         }
                     };
+
+                    // Each axis is scaled (and flipped) on its own.
+                    let axis = |scroll: f32, sensitivity: f32, invert: bool| {
+                        let direction = if invert { -1.0 } else { 1.0 };
+                        (scroll * sensitivity * direction).round() as i32
+                    };
+                    let (sensitivity, invert) = (state_ref.scroll_sensitivity, state_ref.scroll_invert);
+                    state_ref.scroll_x = axis(x, sensitivity.0, invert.0);
+                    state_ref.scroll_y = axis(y, sensitivity.1, invert.1);
+                    state_ref.scroll = state_ref.scroll_x + state_ref.scroll_y;
                 }
                 glutin::event::WindowEvent::MouseInput { state, button, .. } => {
                     let key = match button {
@@ -131,15 +568,77 @@ pub fn main<T: 'static>(
                         glutin::event::ElementState::Pressed => {
                             state_ref.keys[key] = true;
                             state_ref.pressed[key] = true;
+
+                            if press.is_none() {
+                                let now = Instant::now();
+                                let pos = Point(state_ref.mouse_x, state_ref.mouse_y);
+
+                                state_ref.double_click = matches!(
+                                    last_click,
+                                    Some((k, t, p)) if k == key
+                                        && now - t <= DOUBLE_CLICK
+                                        && within_slop(pos - p)
+                                );
+
+                                // A third click starts over, rather than
+                                // making another double click.
+                                last_click = if state_ref.double_click {
+                                    None
+                                } else {
+                                    Some((key, now, pos))
+                                };
+
+                                press = Some((key, now));
+                                state_ref.drag_start = Some(pos);
+                                state_ref.drag_delta = Point(0, 0);
+                                state_ref.long_press = false;
+                            }
+                        }
+                        glutin::event::ElementState::Released => {
+                            state_ref.keys[key] = false;
+
+                            if matches!(press, Some((k, _)) if k == key) {
+                                press = None;
+                                state_ref.drag_start = None;
+                                state_ref.drag_delta = Point(0, 0);
+                                state_ref.long_press = false;
+                            }
                         }
-                        glutin::event::ElementState::Released => state_ref.keys[key] = false,
                     }
                 }
 
+                glutin::event::WindowEvent::CursorEntered { .. } => {
+                    state_ref.mouse_in_window = true;
+                }
+
+                glutin::event::WindowEvent::CursorLeft { .. } => {
+                    state_ref.mouse_in_window = false;
+                }
+
                 glutin::event::WindowEvent::CursorMoved { position, .. } => {
                     let (x, y): (f64, f64) = position.into();
-                    state_ref.mouse_x = gl2gj::pxcoord(x, state_ref.width);
-                    state_ref.mouse_y = gl2gj::pxcoord(-y, state_ref.height);
+                    let window_size = (state_ref.width, state_ref.height);
+
+                    // With `pixel_perfect` or `virtual_size`, the scene
+                    // only covers part of the window.
+                    let (x, y, w, h) = match letterbox(state_ref, window_size) {
+                        Some((_, (left, bottom, w, h))) => {
+                            let top = window_size.1.saturating_sub(bottom + h);
+                            (x - left as f64, y - top as f64, w, h)
+                        }
+                        None => (x, y, window_size.0, window_size.1),
+                    };
+
+                    let (span_x, span_y) = state_ref
+                        .virtual_size
+                        .filter(|&(w, h)| w > 0 && h > 0)
+                        .unwrap_or((helpers::SCREEN_HEIGHT, helpers::SCREEN_HEIGHT));
+                    state_ref.mouse_x = gl2gj::pxcoord(x, w, span_x);
+                    state_ref.mouse_y = gl2gj::pxcoord(-y, h, span_y);
+
+                    if let Some(start) = state_ref.drag_start {
+                        state_ref.drag_delta = Point(state_ref.mouse_x, state_ref.mouse_y) - start;
+                    }
                 }
 
                 _ => {}
@@ -151,76 +650,329 @@ pub fn main<T: 'static>(
 
             glutin::event::Event::RedrawRequested(_) => {
                 let world_ref = world.as_mut().unwrap();
-                if onloop(state_ref, world_ref, &mut audio) {
+
+                // Only keys the gamepad changed are touched, so it
+                // doesn't release keys held on the keyboard.
+                let held = state_ref.gamepad_map.keys(&state_ref.gamepad);
+                for key in Key::ALL {
+                    if held[key] != gamepad_keys[key] {
+                        state_ref.keys[key] = held[key];
+                        state_ref.pressed[key] |= held[key];
+                    }
+                }
+                gamepad_keys = held;
+
+                if let Some((_, t)) = press {
+                    if t.elapsed() >= LONG_PRESS && within_slop(state_ref.drag_delta) {
+                        state_ref.long_press = true;
+                    }
+                }
+
+                if loader
+                    .as_mut()
+                    .is_some_and(|(loader, _)| loader.poll(state_ref, world_ref))
+                {
+                    loader = None;
+                }
+
+                let quit = match &loader {
+                    Some((loader, screen)) => {
+                        screen(state_ref, world_ref, loader.progress.get())
+                    }
+                    None => {
+                        onloop(state_ref, world_ref, &mut audio)
+                            || scene::run(state_ref, world_ref, &mut audio)
+                    }
+                };
+
+                if quit {
                     control_flow.set_exit();
-                    close(state.take().unwrap(), world.take().unwrap());
+                    close(state.take().unwrap(), world.take().unwrap(), &mut audio);
                     return;
                 }
 
-                state_ref.delta = (Instant::now() - last).as_millis();
-                if state_ref.delta < state_ref.fps {
-                    thread::sleep(Duration::from_millis(
-                        (state_ref.fps - state_ref.delta) as u64,
-                    ));
-                    state_ref.delta = state_ref.fps;
+                audio.update(world_ref);
+
+                if state_ref.cursor_icon != cursor_icon {
+                    cursor_icon = state_ref.cursor_icon;
+                    display.gl_window().window().set_cursor_icon(cursor_icon);
+                }
+
+                if state_ref.window_mode != window_mode {
+                    window_mode = state_ref.window_mode.clone();
+                    display.gl_window().window().set_fullscreen(window_mode.fullscreen());
+                }
+
+                if state_ref.min_size != min_size {
+                    min_size = state_ref.min_size;
+                    display.gl_window().window().set_min_inner_size(
+                        min_size.map(|(w, h)| glutin::dpi::LogicalSize::new(w, h)),
+                    );
+                }
+
+                if state_ref.max_size != max_size {
+                    max_size = state_ref.max_size;
+                    display.gl_window().window().set_max_inner_size(
+                        max_size.map(|(w, h)| glutin::dpi::LogicalSize::new(w, h)),
+                    );
                 }
+
+                let elapsed = last.elapsed();
+                let frame_time = state_ref.frame_time();
+                if elapsed < frame_time {
+                    thread::sleep(frame_time - elapsed);
+                }
+                state_ref.delta = elapsed.max(frame_time).as_millis();
                 last = Instant::now();
 
+                #[cfg(feature = "hot-reload")]
+                if let Some(hot_reload) = hot_reload.as_mut() {
+                    hot_reload.update(world_ref);
+                }
+
                 let mut target = display.draw();
+                let window_size = target.get_dimensions();
+                let letterbox = letterbox(state_ref, window_size);
+                state_ref.framebuffer = letterbox.map_or(window_size, |(size, _)| size);
+                state_ref.scale_factor = display.gl_window().window().scale_factor();
+                helpers::set_screen_height(
+                    state_ref
+                        .virtual_size
+                        .filter(|&(w, h)| w > 0 && h > 0)
+                        .map_or(helpers::SCREEN_HEIGHT, |(_, h)| h),
+                );
                 // if unsafe { *SPRITES_CHANGED } {
                 //     sprite_cache = helpers::sprite_filter(sprites_ref.as_ref().clone());
                 //     unsafe { *SPRITES_CHANGED = false };
                 // }
 
-                if let Some(col) = state_ref.clear_color {
-                    let col = col.to_f32();
-                    target.clear_color_and_depth((col[0], col[1], col[2], col[3]), 1.0);
-                }
+                // When clearing is turned off, clear one last time with the
+                // previous color, so the frames after don't ghost over a
+                // stale one.
+                let clear = state_ref.clear_color.or(last_clear_color.take());
+                last_clear_color = state_ref.clear_color;
+
+                let overlay = stats_overlay(state_ref, world_ref.len());
 
-                let mut sorted = Vec::new();
+                let mut sprites = Vec::new();
                 macro_rules! draw_sprites {
                     ( $( $sprite_type:ident ),* ) => {$(
-                        let mut query = world_ref.query::<(&$sprite_type, &Point)>();
-                        for (id, (sprite, pos)) in query.iter() {
+                        let mut query = world_ref.query::<(
+                            &$sprite_type,
+                            Option<&Point>,
+                            Option<&FPoint>,
+                            SpriteComponents<'_>,
+                        )>();
+                        for (id, (sprite, pos, fpos, components)) in query.iter() {
+                            let (angle, color, depth, fill, stroke_weight, join, cap, stroke, order, on_top, parent) =
+                                components;
+
+                            // An `FPoint` overrides the `Point`, keeping its fraction.
+                            let (pos, frac) = match (fpos, pos) {
+                                (Some(fpos), _) => {
+                                    let floor = fpos.floor();
+                                    (floor, *fpos - FPoint::from(floor))
+                                }
+                                (None, Some(pos)) => (*pos, FPoint(0.0, 0.0)),
+                                (None, None) => continue,
+                            };
+
                             let mut ex = SpriteData::new();
+
+                            let angle = angle.map_or(ex.angle, |angle| **angle);
+                            let (pos, angle) = match parent {
+                                Some(_) => graphics::resolve_transform(world_ref, id, pos, angle),
+                                None => (pos, angle),
+                            };
                             ex.x = pos.0;
                             ex.y = pos.1;
+                            ex.frac = (frac.0, frac.1);
+                            ex.angle = angle;
 
-                            if let Ok(angle) = world_ref.get::<&Angle>(id) {
-                                ex.angle = **angle;
-                            }
-
-                            if let Ok(color) = world_ref.get::<&Color>(id) {
+                            if let Some(color) = color {
                                 ex.color = *color;
                             }
 
-                            if let Ok(depth) = world_ref.get::<&Depth>(id) {
+                            if let Some(depth) = depth {
                                 ex.depth = **depth;
                             }
 
-                            if let Ok(fill) = world_ref.get::<&Fill>(id) {
+                            if let Some(fill) = fill {
                                 ex.fill = **fill;
                             }
 
-                            if let Ok(stroke_weight) = world_ref.get::<&StrokeWeight>(id) {
+                            if let Some(stroke_weight) = stroke_weight {
                                 ex.stroke_weight = **stroke_weight;
                             }
 
-                            sorted.push((Sprite::$sprite_type(sprite), ex));
+                            if let Some(join) = join {
+                                ex.join = *join;
+                            }
+
+                            if let Some(cap) = cap {
+                                ex.cap = *cap;
+                            }
+
+                            // Ties are broken by entity id, the same as `pick`,
+                            // so the frontmost sprite is also the one picked.
+                            let on_top = on_top.is_some();
+                            let key = (order.map(|order| **order), id.id());
+                            let sprite = Sprite::$sprite_type(sprite);
+                            sprites.push((on_top, key, sprite, ex));
+
+                            // The outline is drawn right after (over) the shape.
+                            if let (true, Some(stroke)) = (sprite.is_shape(), stroke) {
+                                let outline = SpriteData {
+                                    fill: false,
+                                    color: stroke.color,
+                                    stroke_weight: stroke.weight,
+                                    ..ex
+                                };
+                                sprites.push((on_top, key, sprite, outline));
+                            }
                         }
                     )*};
                 }
 
                 draw_sprites!(Rect, Circle, Triangle, Text, Texture);
-                sorted.sort_by(|(_, ex1), (_, ex2)| ex2.depth.cmp(&ex1.depth));
-                for (sprite, ex) in sorted.into_iter().filter(|(_, ex)| ex.depth > 0) {
-                    sprite.draw(&mut target, ex, &display, &shaders);
+
+                // Sprites without a `Point` (or `FPoint`) are never drawn,
+                // which is an easy mistake to make.
+                if state_ref.debug {
+                    graphics::warn_unplaced(world_ref, &mut unplaced);
+                }
+
+                // Overlays ignore depth entirely (even `Depth(0)`), and are
+                // drawn last in entity id order. Sprites with an `Order` are
+                // drawn above the ones without.
+                let (mut on_top, mut sorted): (Vec<_>, Vec<_>) =
+                    sprites.into_iter().partition(|(on_top, _, _, _)| *on_top);
+
+                let total = sorted.len();
+                sorted.retain(|(_, _, _, ex)| ex.depth > 0);
+
+                let mut stats = RenderStats {
+                    sprites_culled: total - sorted.len(),
+                    ..Default::default()
+                };
+
+                sorted.sort_by_key(|(_, key, _, ex)| (std::cmp::Reverse(ex.depth), *key));
+                on_top.sort_by_key(|(_, key, _, _)| *key);
+
+                // Queued sprites are drawn over the scene, but are
+                // still moved by `render_offset` and `render_pass`.
+                let queue = std::mem::take(&mut state_ref.queue);
+                let mut sorted: Vec<_> = sorted
+                    .into_iter()
+                    .chain(on_top)
+                    .map(|(_, _, sprite, ex)| (sprite, ex))
+                    .chain(queue.iter().map(|(queued, ex)| (queued.sprite(), *ex)))
+                    .collect();
+                for (_, ex) in &mut sorted {
+                    ex.x += state_ref.render_offset.0;
+                    ex.y += state_ref.render_offset.1;
+                    ex.text_scale = state_ref.ui_scale;
+                    if let Some(render_pass) = state_ref.render_pass {
+                        render_pass(ex);
+                    }
+
+                    if let Some(grid) = state_ref.snap_to_grid {
+                        *ex = ex.snap_to_grid(grid);
+                    }
                 }
 
+                // The overlay ignores `render_offset`, `render_pass`, and
+                // `viewports`, and is drawn over everything.
+                let overlay: Vec<_> = overlay
+                    .iter()
+                    .map(|(text, ex)| (Sprite::Text(text), *ex))
+                    .collect();
+                let viewports = &state_ref.viewports;
+
+                if let Some(((w, h), (left, bottom, scaled_w, scaled_h))) = letterbox {
+                    let ((color, depth), fresh) = canvas(&display, canvas_cache.take(), (w, h));
+
+                    // A new canvas starts out as garbage, so it has to be
+                    // cleared even if clearing is turned off.
+                    let clear = clear.or(fresh.then(|| Color::new(0, 0, 0, 255)));
+
+                    {
+                        let mut surface = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(
+                            &display, &color, &depth,
+                        )
+                        .expect("failed to create offscreen canvas");
+
+                        draw_frame(
+                            &mut surface,
+                            (w, h),
+                            clear,
+                            &sorted,
+                            &overlay,
+                            viewports,
+                            &mut viewport_canvases,
+                            &display,
+                            &shaders,
+                            &mut stats,
+                        );
+
+                        target.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+                        surface.blit_color(
+                            &glium::Rect {
+                                left: 0,
+                                bottom: 0,
+                                width: w,
+                                height: h,
+                            },
+                            &target,
+                            &glium::BlitTarget {
+                                left,
+                                bottom,
+                                width: scaled_w as i32,
+                                height: scaled_h as i32,
+                            },
+                            glium::uniforms::MagnifySamplerFilter::Nearest,
+                        );
+                        stats.draw_calls += 1;
+                    }
+
+                    canvas_cache = Some((color, depth));
+                } else {
+                    canvas_cache = None;
+                    draw_frame(
+                        &mut target,
+                        window_size,
+                        clear,
+                        &sorted,
+                        &overlay,
+                        viewports,
+                        &mut viewport_canvases,
+                        &display,
+                        &shaders,
+                        &mut stats,
+                    );
+                }
+
+                read_pixels(state_ref, &target, &display, window_size);
                 target.finish().expect("failed to swap buffers");
+                state_ref.render_stats = stats;
+
+                if state_ref.visible != visible {
+                    visible = state_ref.visible;
+                    display.gl_window().window().set_visible(visible);
+                }
+                shaders.textures.prune();
+                shaders.glyphs.prune();
+                shaders.circles.prune();
+
+                if let Some(post_render) = post_render {
+                    post_render(state_ref);
+                }
 
                 state_ref.pressed = Keys::new();
                 state_ref.scroll = 0;
+                state_ref.scroll_x = 0;
+                state_ref.scroll_y = 0;
+                state_ref.double_click = false;
             }
 
             _ => {}
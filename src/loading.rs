@@ -0,0 +1,138 @@
+//! Loading assets on a separate thread, with a loading screen,
+//! so the window doesn't freeze while a game starts up.
+//!
+//! Pass `loading` to [`genji::init`](crate::init), and write
+//! three more functions: `load` runs on its own thread (and can
+//! report its [`Progress`]), `loading_screen` runs every frame
+//! instead of `onloop` until loading is done, and then `loaded`
+//! receives whatever `load` returned. `init` still runs first,
+//! so the loading screen has a state and world to draw with.
+//!
+//! ```ignore
+//! # use genji::{prelude::*, loading::Progress};
+//! #[genji::init(loading)]
+//! type State = GameState<()>;
+//!
+//! struct Assets {
+//!     player: Sound,
+//! }
+//!
+//! fn load(progress: &Progress) -> Assets {
+//!     let player = Audio::sound_from_file("player.ogg", SoundSettings::default()).unwrap();
+//!     progress.set(1.0);
+//!     Assets { player }
+//! }
+//!
+//! fn loading_screen(state: &mut State, world: &mut World, progress: f32) -> bool {
+//!     // Draw a progress bar...
+//!     false
+//! }
+//!
+//! fn loaded(state: &mut State, world: &mut World, assets: Assets) {
+//!     // Spawn the game...
+//! }
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{ecs::World, state::GameState};
+
+/// How far along loading is, from `0.0` to `1.0`. Shared
+/// between the loading thread and the loading screen.
+#[derive(Debug, Clone, Default)]
+pub struct Progress(Arc<AtomicU32>);
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the progress, clamped to `0.0..=1.0`.
+    pub fn set(&self, progress: f32) {
+        self.0
+            .store(progress.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns the progress.
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// A user function run every frame while loading, instead of
+/// `onloop`, with the current progress. Returning true closes
+/// genji, like `onloop`.
+pub type LoadingScreen<T> = fn(&mut GameState<T>, &mut World, f32) -> bool;
+
+/// The user functions for a loading screen. Created by
+/// [`genji::init`](crate::init) when passed `loading`.
+#[doc(hidden)]
+pub struct Loading<T> {
+    start: Box<dyn FnOnce() -> Loader<T>>,
+    screen: LoadingScreen<T>,
+}
+
+impl<T> Loading<T> {
+    pub fn new<A: Send + 'static>(
+        load: fn(&Progress) -> A,
+        screen: LoadingScreen<T>,
+        loaded: fn(&mut GameState<T>, &mut World, A),
+    ) -> Self
+    where
+        T: 'static,
+    {
+        let start = move || {
+            let progress = Progress::new();
+            let thread = {
+                let progress = progress.clone();
+                thread::spawn(move || load(&progress))
+            };
+
+            let mut thread: Option<JoinHandle<A>> = Some(thread);
+            Loader {
+                progress,
+                poll: Box::new(move |state, world| {
+                    let Some(thread) = thread.take_if(|thread| thread.is_finished()) else {
+                        return false;
+                    };
+
+                    let assets = thread.join().expect("loading thread panicked");
+                    loaded(state, world, assets);
+                    true
+                }),
+            }
+        };
+
+        Self {
+            start: Box::new(start),
+            screen,
+        }
+    }
+
+    /// Starts the loading thread.
+    pub(crate) fn start(self) -> (Loader<T>, LoadingScreen<T>) {
+        ((self.start)(), self.screen)
+    }
+}
+
+/// Hands the assets to `loaded` if the thread is done,
+/// returning whether or not it was.
+type Poll<T> = Box<dyn FnMut(&mut GameState<T>, &mut World) -> bool>;
+
+/// A running loading thread.
+pub(crate) struct Loader<T> {
+    pub(crate) progress: Progress,
+    poll: Poll<T>,
+}
+
+impl<T> Loader<T> {
+    pub(crate) fn poll(&mut self, state: &mut GameState<T>, world: &mut World) -> bool {
+        (self.poll)(state, world)
+    }
+}
@@ -41,15 +41,17 @@ macro_rules! use_files {
 }
 
 pub use crate::{
-    audio::{Audio, MusicStore, Sound, SoundSettings, SoundStore},
-    ecs::{Entity, World},
+    audio::{Audio, MusicStore, PlaybackExt, Sound, SoundSettings, SoundStore},
+    ecs::{Entity, Order, World, WorldExt},
     graphics::{
-        sprite::{self, ImageFormat},
-        spritemap::Spritemap,
-        Angle, Color, Depth, Fill, StrokeWeight,
+        atlas::Atlas,
+        sprite::{self, ImageFormat, IntoSprite, SpriteData, SpriteExt},
+        spritemap::{SpriteAnimation, Spritemap},
+        AlwaysOnTop, Angle, Color, Depth, Fill, Parent, Stroke, StrokeCap, StrokeJoin,
+        StrokeWeight,
     },
-    input::Key,
-    shape::{self, Circle, Contains, Point, Rect, Triangle},
-    state::GameState,
+    input::{CursorIcon, Key},
+    shape::{self, Circle, Contains, FPoint, Point, Rect, Triangle},
+    state::{GameState, WindowMode},
     use_file, use_files,
 };
@@ -0,0 +1,245 @@
+//! An optional way to structure a game into scenes, such as
+//! a menu, the gameplay, and a pause screen.
+//!
+//! Each [`Scene`] gets its own `update`, and tells genji
+//! where to go next by returning a [`Transition`]. Scenes
+//! live on a stack in `GameState.scenes`, which genji runs
+//! every frame, right after `onloop`; only the top scene
+//! is updated, so pushing a pause screen freezes the game
+//! underneath it.
+//!
+//! ```
+//! # use genji::{prelude::*, scene::{Scene, Transition}};
+//! #[derive(Clone)]
+//! struct Menu;
+//!
+//! impl Scene<()> for Menu {
+//!     fn update(&mut self, state: &mut GameState<()>, _: &mut World, _: &mut Audio) -> Transition<()> {
+//!         if state.pressed[Key::Enter] {
+//!             Transition::Switch(Box::new(Level(1)))
+//!         } else {
+//!             Transition::None
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Level(u32);
+//!
+//! impl Scene<()> for Level {
+//!     fn on_enter(&mut self, _: &mut GameState<()>, world: &mut World) {
+//!         world.spawn((shape::rect(20, 20), Point(0, 0)));
+//!     }
+//!
+//!     fn update(&mut self, state: &mut GameState<()>, _: &mut World, _: &mut Audio) -> Transition<()> {
+//!         if state.pressed[Key::Esc] {
+//!             Transition::Quit
+//!         } else {
+//!             Transition::None
+//!         }
+//!     }
+//! }
+//!
+//! // In init...
+//! # let mut state = GameState::new((), "", None, None, None, None);
+//! state.scenes.push(Box::new(Menu));
+//! ```
+
+use std::fmt;
+
+use crate::{
+    audio::Audio,
+    ecs::{World, WorldExt},
+    state::GameState,
+};
+
+/// What to do after a [`Scene`] updates.
+pub enum Transition<T> {
+    /// Stay in the current scene.
+    None,
+    /// Put a scene on top of the current one, e.g. a pause
+    /// screen. The current scene stops updating until the
+    /// new one is popped.
+    Push(Box<dyn Scene<T>>),
+    /// Leave the current scene, returning to the one below.
+    /// Popping the last scene closes genji.
+    Pop,
+    /// Replace the current scene. Every entity without a
+    /// [`Keep`](crate::ecs::Keep) is despawned in between.
+    Switch(Box<dyn Scene<T>>),
+    /// Close genji (running `close` as usual).
+    Quit,
+}
+
+/// One part of a game, e.g. a menu or a level. See the [module docs](self).
+///
+/// Scenes must be `Clone`, so that `GameState` can be cloned.
+pub trait Scene<T>: CloneScene<T> {
+    /// Run when the scene becomes the current one.
+    fn on_enter(&mut self, _state: &mut GameState<T>, _world: &mut World) {}
+
+    /// Run when the scene is popped or switched away from
+    /// (but not when another scene is pushed on top of it).
+    fn on_exit(&mut self, _state: &mut GameState<T>, _world: &mut World) {}
+
+    /// Run every frame while the scene is on top.
+    fn update(
+        &mut self,
+        state: &mut GameState<T>,
+        world: &mut World,
+        audio: &mut Audio,
+    ) -> Transition<T>;
+
+    /// Run every frame after `update` (and any transition),
+    /// on the scene that's now on top. Use it for e.g.
+    /// [`GameState::draw_points`].
+    fn draw(&mut self, _state: &mut GameState<T>, _world: &mut World) {}
+}
+
+/// Lets boxed [`Scene`]s be cloned. Implemented for
+/// every scene that's `Clone`.
+pub trait CloneScene<T> {
+    fn clone_scene(&self) -> Box<dyn Scene<T>>;
+}
+
+impl<T, S: Scene<T> + Clone + 'static> CloneScene<T> for S {
+    fn clone_scene(&self) -> Box<dyn Scene<T>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The stack of [`Scene`]s, in `GameState.scenes`.
+/// Empty by default, in which case it does nothing.
+pub struct SceneStack<T> {
+    /// Each scene, and whether it has been entered yet.
+    scenes: Vec<(Box<dyn Scene<T>>, bool)>,
+}
+
+impl<T> SceneStack<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts a scene on top of the stack. Its `on_enter` is
+    /// run the next time the stack updates.
+    pub fn push(&mut self, scene: Box<dyn Scene<T>>) {
+        self.scenes.push((scene, false));
+    }
+
+    /// Returns how many scenes are on the stack.
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns whether or not the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Runs the top scene for a frame, returning whether or not
+    /// genji should close. Genji does this every frame with
+    /// `GameState.scenes`; only call it yourself for a stack
+    /// kept somewhere else.
+    pub fn update(
+        &mut self,
+        state: &mut GameState<T>,
+        world: &mut World,
+        audio: &mut Audio,
+    ) -> bool {
+        if self.enter(state, world) {
+            return false;
+        }
+
+        let Some((scene, _)) = self.scenes.last_mut() else {
+            return false;
+        };
+
+        match scene.update(state, world, audio) {
+            Transition::None => {}
+            Transition::Push(next) => {
+                self.push(next);
+            }
+            Transition::Pop => {
+                if let Some((mut scene, _)) = self.scenes.pop() {
+                    scene.on_exit(state, world);
+                }
+
+                if self.scenes.is_empty() {
+                    return true;
+                }
+            }
+            Transition::Switch(next) => {
+                if let Some((mut scene, _)) = self.scenes.pop() {
+                    scene.on_exit(state, world);
+                }
+
+                world.clear_matching::<()>();
+                self.push(next);
+            }
+            Transition::Quit => return true,
+        }
+
+        self.enter(state, world);
+        if let Some((scene, _)) = self.scenes.last_mut() {
+            scene.draw(state, world);
+        }
+
+        false
+    }
+
+    /// Enters the top scene if it hasn't been yet. Returns
+    /// true if the stack is empty.
+    fn enter(&mut self, state: &mut GameState<T>, world: &mut World) -> bool {
+        match self.scenes.last_mut() {
+            Some((scene, entered)) => {
+                if !*entered {
+                    *entered = true;
+                    scene.on_enter(state, world);
+                }
+
+                false
+            }
+            None => true,
+        }
+    }
+}
+
+impl<T> Default for SceneStack<T> {
+    fn default() -> Self {
+        Self { scenes: Vec::new() }
+    }
+}
+
+impl<T> Clone for SceneStack<T> {
+    fn clone(&self) -> Self {
+        Self {
+            scenes: self
+                .scenes
+                .iter()
+                .map(|(scene, entered)| (scene.clone_scene(), *entered))
+                .collect(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SceneStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SceneStack")
+            .field("len", &self.scenes.len())
+            .finish()
+    }
+}
+
+/// Runs `state.scenes` for a frame, returning whether
+/// or not genji should close.
+pub(crate) fn run<T>(state: &mut GameState<T>, world: &mut World, audio: &mut Audio) -> bool {
+    let mut scenes = std::mem::take(&mut state.scenes);
+    let quit = scenes.update(state, world, audio);
+
+    // Scenes pushed onto `state.scenes` directly while
+    // it was taken go on top.
+    let pushed = std::mem::replace(&mut state.scenes, scenes);
+    state.scenes.scenes.extend(pushed.scenes);
+
+    quit
+}
@@ -6,7 +6,8 @@
 //! implementations for all geometric sprites.
 //!
 //! Also provides the foundational [`Point`]
-//! struct, which is a basic 2D vector.
+//! struct, which is a basic 2D vector, and
+//! [`FPoint`] for drawing between coordinates.
 
 use std::ops::{Add, Div, Mul, Sub};
 
@@ -30,7 +31,14 @@ use crate::graphics::sprite::Texture;
 ///     Point(25, 25),
 /// ));
 /// ```
-#[derive(Clone, Copy, PartialEq, Eq)]
+///
+/// The operators (`+`, `-`, `*`, `/`) behave like they do on
+/// `i32`: overflowing panics in debug builds and wraps in
+/// release builds. When that's possible, e.g. when accumulating
+/// a position every frame, use [`Point::saturating_add`] and
+/// friends instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point(pub i32, pub i32);
 
 impl Point {
@@ -47,6 +55,24 @@ impl Point {
         let len = self.len();
         (self.0 as f32 / len, self.1 as f32 / len)
     }
+
+    /// Adds two vectors, stopping at `i32::MIN`/`i32::MAX`
+    /// instead of overflowing.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0), self.1.saturating_add(rhs.1))
+    }
+
+    /// Subtracts two vectors, stopping at `i32::MIN`/`i32::MAX`
+    /// instead of overflowing.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0), self.1.saturating_sub(rhs.1))
+    }
+
+    /// Scales the vector, stopping at `i32::MIN`/`i32::MAX`
+    /// instead of overflowing.
+    pub fn saturating_mul(self, rhs: i32) -> Self {
+        Self(self.0.saturating_mul(rhs), self.1.saturating_mul(rhs))
+    }
 }
 
 impl PartialOrd for Point {
@@ -104,7 +130,60 @@ impl Mul for Point {
     }
 }
 
-fn pivot(point: Point, angle: f32, pivot: Point) -> Point {
+/// A sprite's position, like [`Point`], but fractional.
+///
+/// When a sprite has an `FPoint`, it's drawn there instead
+/// of at its `Point`, so slow or zoomed-in movement doesn't
+/// visibly step from one coordinate to the next. A sprite
+/// can have either, or both: keep a `Point` for logic that
+/// wants integers (e.g. [`Contains`]), and update it from
+/// the `FPoint` with [`FPoint::round`].
+///
+/// ```
+/// # use genji::prelude::*;
+/// # let mut world = World::new();
+/// world.spawn((shape::rect(20, 20), FPoint(0.5, 10.25)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FPoint(pub f32, pub f32);
+
+impl FPoint {
+    /// Returns the nearest [`Point`].
+    pub fn round(&self) -> Point {
+        Point(self.0.round() as i32, self.1.round() as i32)
+    }
+
+    /// Returns the [`Point`] at or below and left of this one.
+    pub fn floor(&self) -> Point {
+        Point(self.0.floor() as i32, self.1.floor() as i32)
+    }
+}
+
+impl From<Point> for FPoint {
+    fn from(point: Point) -> Self {
+        Self(point.0 as f32, point.1 as f32)
+    }
+}
+
+impl Add for FPoint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for FPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+/// Rotates `point` counter-clockwise around `pivot` by `angle` degrees.
+pub(crate) fn pivot(point: Point, angle: f32, pivot: Point) -> Point {
     let angle = angle.to_radians();
 
     Point(
@@ -135,10 +214,50 @@ fn triangle_points(pos: Point, mut w: i32, mut h: i32, o: i32) -> (Point, Point,
 }
 
 pub trait Contains {
+    /// Returns whether or not the shape, centered on `pos` and
+    /// rotated by `angle` degrees, contains `point`.
+    ///
+    /// Like sprites, a positive `angle` turns the shape clockwise,
+    /// so this matches what's drawn for the same [`Angle`](crate::graphics::Angle).
     fn contains(&self, pos: Point, point: Point, angle: f32) -> bool {
+        // Undo the (clockwise) rotation by turning the point counter-clockwise.
         self.contains_corrected(pos, pivot(point, angle, pos))
     }
 
+    /// Like [`Contains::contains`], but for a shape that's also
+    /// scaled (horizontally and vertically) around `pos`, so that
+    /// hit-testing matches a scaled sprite.
+    ///
+    /// A zero scale contains nothing.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// let rect = shape::rect(20, 10);
+    ///
+    /// assert!(!rect.contains(Point(0, 0), Point(15, 0), 0.0));
+    /// assert!(rect.contains_transformed(Point(0, 0), Point(15, 0), 0.0, (2.0, 1.0)));
+    /// ```
+    fn contains_transformed(
+        &self,
+        pos: Point,
+        point: Point,
+        angle: f32,
+        scale: (f32, f32),
+    ) -> bool {
+        if scale.0 == 0.0 || scale.1 == 0.0 {
+            return false;
+        }
+
+        // Undo the rotation, then the scale, to get back to the shape's own space.
+        let point = pivot(point, angle, pos) - pos;
+        let point = Point(
+            (point.0 as f32 / scale.0).round() as i32,
+            (point.1 as f32 / scale.1).round() as i32,
+        );
+
+        self.contains_corrected(pos, point + pos)
+    }
+
     fn contains_corrected(&self, pos: Point, point: Point) -> bool;
 }
 
@@ -192,6 +311,7 @@ impl Contains for Texture {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
     pub w: i32,
     pub h: i32,
@@ -213,6 +333,7 @@ pub struct Rect {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle {
     pub r: i32,
 }
@@ -237,6 +358,7 @@ pub struct Circle {
 /// ));
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     pub w: i32,
     pub h: i32,
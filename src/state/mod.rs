@@ -25,11 +25,30 @@
 //! # }
 //! ```
 
-use std::ops::{Deref, DerefMut};
+use std::{
+    any::Any,
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
+use ab_glyph::FontArc;
+use glium::glutin::window::Fullscreen;
 
 use crate::ecs::EntityStore;
-use crate::graphics::Color;
-use crate::input::Keys;
+use crate::graphics::{
+    sprite::{Batch, Queued, SpriteData, Text},
+    Color, GraphicsInfo, StrokeCap, StrokeJoin, Viewport,
+};
+use crate::input::{CursorIcon, EventProxy, Gamepad, GamepadMap, Keys};
+use crate::scene::SceneStack;
+use crate::shape::{Point, Rect};
+
+mod resources;
+pub use resources::Resources;
+
+/// A resolution, bit depth and refresh rate the monitor supports,
+/// borrowed from [`glutin`](glium::glutin). See [`WindowMode`].
+pub use glium::glutin::monitor::VideoMode;
 
 /// Holds the generic state for the game. This
 /// can be thought of roughly as your window.
@@ -63,6 +82,22 @@ pub struct GameState<T> {
     pub width: u32,
     pub height: u32,
 
+    /// The smallest size the window can be resized to.
+    /// Can be changed at any time. Defaults to None.
+    pub min_size: Option<(u32, u32)>,
+    /// The largest size the window can be resized to.
+    /// Can be changed at any time. Defaults to None.
+    pub max_size: Option<(u32, u32)>,
+    /// An aspect ratio, e.g. `(16, 9)`, that the window snaps back
+    /// to whenever it's resized, keeping whichever of its width
+    /// and height changes it least. Unlike `virtual_size`, this
+    /// changes the window itself, so there are no black bars.
+    /// Can be changed at any time. Defaults to None.
+    pub lock_aspect: Option<(u32, u32)>,
+
+    /// The color the screen is cleared to every frame.
+    /// Can be changed at any time; if set to None, the screen
+    /// is cleared one last time, then never again.
     pub clear_color: Option<Color>,
 
     pub state: T,
@@ -71,32 +106,206 @@ pub struct GameState<T> {
     /// Keys that were pressed this frame.
     pub pressed: Keys,
 
+    /// The state of the gamepad, which genji doesn't read itself;
+    /// see [`Gamepad`]. Mapped onto `keys` and `pressed` via
+    /// `gamepad_map` at the start of each frame.
+    pub gamepad: Gamepad,
+    /// Which keys the gamepad's inputs press.
+    /// Defaults to no bindings.
+    pub gamepad_map: GamepadMap,
+
     pub store: EntityStore,
 
+    /// Singletons that don't belong to any entity,
+    /// such as the score or the current level.
+    /// See [`GameState::resource`].
+    pub resources: Resources,
+
+    /// The game's scenes, if it uses them. Run every frame,
+    /// right after `onloop`. See [`scene`](crate::scene).
+    pub scenes: SceneStack<T>,
+
+    /// The target frames per second. Can be changed at any
+    /// time; `0` means uncapped.
     pub fps: u128,
+    /// How long the last frame took, in milliseconds.
     pub delta: u128,
+    /// When genji started running, before `init`. Real time,
+    /// unaffected by the game itself; see [`GameState::uptime`].
+    pub start_time: Instant,
 
     pub mouse_x: i32,
     pub mouse_y: i32,
+    /// Whether or not the mouse is over the window. While it
+    /// isn't, `mouse_x` and `mouse_y` hold where it left.
+    /// Defaults to true.
+    pub mouse_in_window: bool,
 
-    /// The change in the scroll wheel this frame, in coordinates.
+    /// The change in the scroll wheel this frame, in coordinates:
+    /// `scroll_x` and `scroll_y` added together, for when the
+    /// direction doesn't matter.
     pub scroll: i32,
+    /// The horizontal scrolling this frame (e.g. from a trackpad),
+    /// in coordinates.
+    pub scroll_x: i32,
+    /// The vertical scrolling this frame, in coordinates.
+    pub scroll_y: i32,
+    /// How much scrolling is scaled by, horizontally and
+    /// vertically, e.g. for a scroll speed setting.
+    /// Defaults to `(1.0, 1.0)`.
+    pub scroll_sensitivity: (f32, f32),
+    /// Whether or not scrolling is flipped, horizontally and
+    /// vertically, e.g. for a natural scrolling setting.
+    /// Defaults to `(false, false)`.
+    pub scroll_invert: (bool, bool),
+
+    /// Where the mouse was when the current drag began, i.e. when
+    /// a mouse button was pressed. None while no button is held.
+    pub drag_start: Option<Point>,
+    /// How far the mouse has moved since `drag_start`, in coordinates.
+    pub drag_delta: Point,
+    /// Whether the held mouse button has been held for half a
+    /// second without the mouse moving (more than a few coordinates).
+    pub long_press: bool,
+    /// Whether a mouse button was pressed twice in quick succession
+    /// (in the same spot) this frame.
+    pub double_click: bool,
+
+    /// Offsets every sprite when drawing, e.g. for screen shake.
+    /// Applied after sorting, right before `render_pass`.
+    /// Defaults to `Point(0, 0)`.
+    pub render_offset: Point,
+
+    /// Run on every visible sprite after sorting, right before it's
+    /// drawn. Use this to apply global effects, like a tint.
+    /// Defaults to None.
+    pub render_pass: Option<fn(&mut SpriteData)>,
+
+    /// A low "design" resolution, in pixels, for crisp pixel art.
+    /// When set, the scene is rendered at exactly this size, then
+    /// scaled up by the largest whole number that fits the window
+    /// (without filtering), with black bars around it.
+    /// Defaults to None.
+    pub pixel_perfect: Option<(u32, u32)>,
+    /// Snaps every drawn sprite to the nearest multiple of this many
+    /// coordinates, e.g. to stop pixel art shimmering as it moves
+    /// at fractional speeds. Only where sprites are *drawn* changes:
+    /// their `Point`s and `FPoint`s keep moving smoothly.
+    ///
+    /// Applied last, after `render_offset` and `render_pass`, so it
+    /// also snaps the camera. For whole pixels, pair it with
+    /// `pixel_perfect` (or `virtual_size`) set to a size as many
+    /// pixels tall as the screen is coordinates tall, so that one
+    /// coordinate is one pixel. Can be changed at any time.
+    /// Defaults to None.
+    pub snap_to_grid: Option<u32>,
+
+    /// A "virtual" screen size, in coordinates. When set, the screen
+    /// is exactly this many coordinates across and tall (instead of
+    /// 400 tall), scaled to fit the window with black bars around
+    /// it. `Point(0, 0)` is still the center. Defaults to None.
+    ///
+    /// Combined with `pixel_perfect`, this only changes the
+    /// coordinates; `pixel_perfect` decides the scaling.
+    pub virtual_size: Option<(u32, u32)>,
+
+    /// Regions of the screen to draw the scene into, each with its
+    /// own camera, e.g. for split-screen. Gaps between them are
+    /// cleared to `clear_color`. If empty, the scene covers the
+    /// whole screen. Defaults to empty.
+    pub viewports: Vec<Viewport>,
+
+    /// Sprites queued by `draw_points`, `draw_rect`, etc.,
+    /// drawn (and cleared) on the next frame.
+    pub(crate) queue: Vec<(Queued, SpriteData)>,
+
+    /// What was drawn last frame, e.g. for a debug overlay.
+    /// Updated every frame, after drawing.
+    pub render_stats: RenderStats,
+
+    /// Whether or not genji warns (on stderr) about common
+    /// mistakes, like a sprite without a `Point`.
+    /// Defaults to true in debug builds.
+    pub debug: bool,
+
+    /// Whether or not to show the fps, frame time, entity count,
+    /// and draw calls in the top-left corner of the screen.
+    /// Needs `stats_font`. Defaults to false.
+    pub show_stats: bool,
+    /// The font for `show_stats`. Genji doesn't come with a font,
+    /// so nothing is shown until one is set. Defaults to None.
+    pub stats_font: Option<FontArc>,
+
+    /// The icon shown for the mouse cursor while it's
+    /// over the window. Defaults to the system default.
+    pub cursor_icon: CursorIcon,
+
+    /// How many times bigger text and [`ui`](crate::ui) widgets
+    /// are drawn, e.g. to suit high-DPI monitors; set it to
+    /// `scale_factor()` to follow the OS. Text grows around its
+    /// own position, and widgets around theirs. Text is rendered
+    /// at the bigger size, so it stays sharp.
+    ///
+    /// This scales *every* [`Text`](crate::graphics::sprite::Text),
+    /// including text placed in the world (e.g. names over
+    /// characters), not just UI text.
+    ///
+    /// Genji's camera only moves sprites (see `render_offset`),
+    /// so this doesn't depend on it; nor does it affect any
+    /// measurements, like [`Text::layout_lines`]. Can be changed
+    /// at any time. Defaults to `1.0`.
+    pub ui_scale: f32,
 
-    /// Whether or not genji closes when the OS asks it to.
+    /// Whether the window is windowed or fullscreen. Can be
+    /// changed at any time. Defaults to `WindowMode::Windowed`.
+    pub window_mode: WindowMode,
+
+    /// Whether or not the window is shown. The window always
+    /// opens hidden and appears once the first frame is drawn;
+    /// set this to false to keep it hidden (e.g. until assets have
+    /// loaded). Can be changed at any time. Defaults to true.
+    pub visible: bool,
+    /// Whether or not the window itself can be see-through,
+    /// e.g. for overlays; use a `clear_color` with an alpha
+    /// below 255. Only read when the window opens, and not
+    /// supported everywhere. Defaults to false.
+    pub transparent: bool,
+
+    /// Whether or not genji closes when the OS asks it to
+    /// (e.g. the window's close button was clicked).
     /// Defaults to true.
+    ///
+    /// Set this to false to handle closing yourself
+    /// via `asked_to_close`.
     pub close_on_request: bool,
     /// If genji has been asked to close by the OS. If `close_on_request`,
-    /// this should never be true.
+    /// genji closes immediately, so `onloop` will never see this as true.
     pub asked_to_close: bool,
+
+    pub(crate) framebuffer: (u32, u32),
+    pub(crate) scale_factor: f64,
+    pub(crate) fps_is_default: bool,
+    pub(crate) refresh_rate: Option<u32>,
+    pub(crate) video_modes: Vec<VideoMode>,
+    pub(crate) graphics_info: GraphicsInfo,
+    /// The points watched by `pixel_at`, their colors as of the
+    /// last frame, and whether they were asked for this frame.
+    pub(crate) pixels: Vec<(Point, Option<Color>, bool)>,
+    pub(crate) event_proxy: Option<EventProxy>,
 }
 
 impl<T> GameState<T> {
     /// Initiates genji's game state. Creates a new window.
     ///
     /// `width` and `height` may be None, defaulting to 640
-    /// and 480 respectively. `fps` defaults to 100.
+    /// and 480 respectively. `fps` defaults to the refresh rate
+    /// of the monitor the window opens on, or 100 if that
+    /// can't be determined.
     ///
     /// If `clear_color` is None, the screen is never cleared.
+    ///
+    /// `close_on_request` defaults to true, so the window
+    /// closes normally when the OS asks it to.
     pub fn new<S: ToString>(
         state: T,
         title: S,
@@ -108,6 +317,7 @@ impl<T> GameState<T> {
         let title = title.to_string();
         let width = width.unwrap_or(640);
         let height = height.unwrap_or(480);
+        let fps_is_default = fps.is_none();
         let fps = fps.unwrap_or(100);
 
         Self {
@@ -115,26 +325,248 @@ impl<T> GameState<T> {
             width,
             height,
 
+            min_size: None,
+            max_size: None,
+            lock_aspect: None,
+
             clear_color,
 
             state,
             keys: Keys::new(),
             pressed: Keys::new(),
 
+            gamepad: Gamepad::new(),
+            gamepad_map: GamepadMap::new(),
+
             store: EntityStore::new(),
+            resources: Resources::new(),
+            scenes: SceneStack::new(),
 
-            fps: 1000 / fps,
+            fps,
             delta: 0,
+            start_time: Instant::now(),
 
             mouse_x: 0,
             mouse_y: 0,
+            mouse_in_window: true,
 
             scroll: 0,
+            scroll_x: 0,
+            scroll_y: 0,
+            scroll_sensitivity: (1.0, 1.0),
+            scroll_invert: (false, false),
+
+            drag_start: None,
+            drag_delta: Point(0, 0),
+            long_press: false,
+            double_click: false,
+
+            render_offset: Point(0, 0),
+            render_pass: None,
+
+            pixel_perfect: None,
+            snap_to_grid: None,
+            virtual_size: None,
+            viewports: Vec::new(),
+            queue: Vec::new(),
+            render_stats: RenderStats::default(),
+            debug: cfg!(debug_assertions),
+            show_stats: false,
+            stats_font: None,
+
+            cursor_icon: CursorIcon::Default,
+            ui_scale: 1.0,
+            window_mode: WindowMode::Windowed,
+            visible: true,
+            transparent: false,
 
             close_on_request: true,
             asked_to_close: false,
+
+            framebuffer: (width, height),
+            scale_factor: 1.0,
+            fps_is_default,
+            refresh_rate: None,
+            video_modes: Vec::new(),
+            graphics_info: GraphicsInfo::default(),
+            pixels: Vec::new(),
+            event_proxy: None,
         }
     }
+
+    /// The OS's scale factor for the window, e.g. `2.0` on many
+    /// high-DPI monitors. Updated every frame; `1.0` until the
+    /// window opens. See `ui_scale`.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// The size, in pixels, of the surface genji renders to.
+    ///
+    /// This can differ from `width` and `height` (e.g. on HiDPI
+    /// displays), and is what genji uses to correct for the
+    /// aspect ratio. Updated every frame, before drawing.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        self.framebuffer
+    }
+
+    /// Draws `points` as dots (one coordinate wide) on the next
+    /// frame only, in a single draw call; no entities needed.
+    ///
+    /// Queued points are drawn over every sprite, in the order
+    /// they were queued. Like sprites, they're moved by
+    /// `render_offset` and go through `render_pass`.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// # let mut state = GameState::new((), "", None, None, None, None);
+    /// let samples = [Point(-10, 4), Point(0, 12), Point(10, 7)];
+    /// state.draw_points(&samples, Color::new(0, 255, 0, 255));
+    /// ```
+    pub fn draw_points(&mut self, points: &[Point], color: Color) {
+        let batch = Batch {
+            points: points.to_vec(),
+            line: false,
+        };
+
+        self.queue
+            .push((Queued::Batch(batch), SpriteData::new().color(color)));
+    }
+
+    /// Draws a line through `points`, `weight` coordinates thick,
+    /// on the next frame only, in a single draw call. Corners
+    /// are rounded. See [`GameState::draw_points`].
+    pub fn draw_polyline(&mut self, points: &[Point], color: Color, weight: u32) {
+        let batch = Batch {
+            points: points.to_vec(),
+            line: true,
+        };
+
+        let ex = SpriteData {
+            color,
+            stroke_weight: weight,
+            join: StrokeJoin::Round,
+            cap: StrokeCap::Round,
+            ..SpriteData::new()
+        };
+        self.queue.push((Queued::Batch(batch), ex));
+    }
+
+    /// Draws a filled `rect` centered on `pos`, on the next frame
+    /// only; no entities needed. See [`GameState::draw_points`].
+    pub fn draw_rect(&mut self, rect: Rect, pos: Point, color: Color) {
+        let ex = SpriteData::new().pos(pos).color(color);
+        self.queue.push((Queued::Rect(rect), ex));
+    }
+
+    /// Draws `text` centered on `pos`, on the next frame only;
+    /// no entities needed. See [`GameState::draw_points`].
+    pub fn draw_text(&mut self, text: &Text, pos: Point, color: Color) {
+        let ex = SpriteData::new().pos(pos).color(color);
+        self.queue.push((Queued::Text(text.clone()), ex));
+    }
+
+    /// How much real time has passed since `start_time`,
+    /// e.g. for timestamps and timeouts.
+    pub fn uptime(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// How long each frame should take to hit `fps`.
+    /// Zero if `fps` is `0` (uncapped).
+    pub fn frame_time(&self) -> Duration {
+        1_000_000_000u128
+            .checked_div(self.fps)
+            .map_or(Duration::ZERO, |nanos| Duration::from_nanos(nanos as u64))
+    }
+
+    /// The refresh rate, in hertz, of the monitor the window
+    /// opened on, if it could be determined.
+    pub fn monitor_refresh_rate(&self) -> Option<f32> {
+        self.refresh_rate.map(|mhz| mhz as f32 / 1000.0)
+    }
+
+    /// The video modes supported by the monitor the window
+    /// opened on, for [`WindowMode::ExclusiveFullscreen`].
+    /// Empty until the window opens, i.e. during `init`.
+    pub fn video_modes(&self) -> &[VideoMode] {
+        &self.video_modes
+    }
+
+    /// Returns the color on screen at `(x, y)` (in coordinates,
+    /// like `mouse_x` and `mouse_y`), e.g. for picking sprites by
+    /// color where `Contains` isn't precise enough.
+    ///
+    /// Reading from the GPU is slow, so genji only reads the
+    /// points asked for: the first call for a point returns None,
+    /// and each frame after returns its color as of the frame
+    /// before. A point is forgotten after a frame without a call.
+    /// Also returns None if the point is off the screen.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// # let mut state = GameState::new((), "", None, None, None, None);
+    /// // In onloop...
+    /// if state.pixel_at(state.mouse_x, state.mouse_y) == Some(Color::new(255, 0, 0, 255)) {
+    ///     // Hovering over something red.
+    /// }
+    /// ```
+    pub fn pixel_at(&mut self, x: i32, y: i32) -> Option<Color> {
+        let point = Point(x, y);
+        match self.pixels.iter_mut().find(|(p, _, _)| *p == point) {
+            Some((_, color, asked)) => {
+                *asked = true;
+                *color
+            }
+            None => {
+                self.pixels.push((point, None, true));
+                None
+            }
+        }
+    }
+
+    /// What the graphics driver supports. Filled in when the
+    /// window opens, so it's empty (default) during `init`.
+    pub fn graphics_info(&self) -> &GraphicsInfo {
+        &self.graphics_info
+    }
+
+    /// Returns a handle for sending custom events to `on_event`
+    /// from other threads. `None` until the window opens, i.e.
+    /// during `init`.
+    pub fn event_proxy(&self) -> Option<EventProxy> {
+        self.event_proxy.clone()
+    }
+
+    /// Stores a resource, returning the previous one of the same type.
+    /// See [`Resources`].
+    pub fn insert_resource<R: Any + Clone>(&mut self, resource: R) -> Option<R> {
+        self.resources.insert(resource)
+    }
+
+    /// Returns a resource if it exists.
+    ///
+    /// ```
+    /// # use genji::prelude::*;
+    /// #[derive(Clone)]
+    /// struct Score(u32);
+    ///
+    /// # let mut state = GameState::new((), "", None, None, None, None);
+    /// state.insert_resource(Score(0));
+    ///
+    /// // In onloop...
+    /// if let Some(score) = state.resource_mut::<Score>() {
+    ///     score.0 += 1;
+    /// }
+    /// ```
+    pub fn resource<R: Any>(&self) -> Option<&R> {
+        self.resources.get()
+    }
+
+    /// Returns a resource mutably if it exists.
+    pub fn resource_mut<R: Any>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
 }
 
 impl<T> Deref for GameState<T> {
@@ -150,3 +582,57 @@ impl<T> DerefMut for GameState<T> {
         &mut self.state
     }
 }
+
+/// How much work genji did to draw a frame.
+/// See [`GameState::render_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// How many draw calls were sent to the GPU, counting
+    /// the copies of offscreen canvases (e.g. for viewports
+    /// or `pixel_perfect`) onto the screen.
+    pub draw_calls: usize,
+    /// How many sprites were drawn. A shape with a
+    /// `Stroke` counts twice, once for its outline.
+    pub sprites_drawn: usize,
+    /// How many sprites were skipped, e.g. because
+    /// they were hidden with `Depth(0)`.
+    pub sprites_culled: usize,
+    /// How many vertices were drawn, across every sprite.
+    pub vertices: usize,
+}
+
+/// How the window is shown. See [`GameState::window_mode`].
+///
+/// ```
+/// # use genji::prelude::*;
+/// # fn dummy(state: &mut GameState<()>) {
+/// // Switch to the monitor's biggest resolution.
+/// if let Some(mode) = state.video_modes().iter().max_by_key(|mode| mode.size().width) {
+///     state.window_mode = WindowMode::ExclusiveFullscreen(mode.clone());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum WindowMode {
+    /// A regular window, with decorations.
+    #[default]
+    Windowed,
+    /// A window covering the whole monitor, without changing
+    /// its resolution. Quick to switch in and out of.
+    BorderlessFullscreen,
+    /// Takes over the monitor, switching it to the given mode
+    /// (one of [`GameState::video_modes`]). Can be faster than
+    /// borderless, but switching is slower and less friendly
+    /// to alt-tabbing.
+    ExclusiveFullscreen(VideoMode),
+}
+
+impl WindowMode {
+    pub(crate) fn fullscreen(&self) -> Option<Fullscreen> {
+        match self {
+            Self::Windowed => None,
+            Self::BorderlessFullscreen => Some(Fullscreen::Borderless(None)),
+            Self::ExclusiveFullscreen(mode) => Some(Fullscreen::Exclusive(mode.clone())),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+};
+
+/// A resource, along with a way to clone it.
+struct Resource {
+    value: Box<dyn Any>,
+    clone: fn(&dyn Any) -> Box<dyn Any>,
+}
+
+impl Clone for Resource {
+    fn clone(&self) -> Self {
+        Self {
+            value: (self.clone)(&*self.value),
+            clone: self.clone,
+        }
+    }
+}
+
+fn clone_resource<R: Any + Clone>(value: &dyn Any) -> Box<dyn Any> {
+    Box::new(
+        value
+            .downcast_ref::<R>()
+            .expect("resource stored under the wrong type")
+            .clone(),
+    )
+}
+
+/// A typed map of singletons, with at most one value per type.
+///
+/// Resources must be `Clone`, so that
+/// [`GameState`](super::GameState) can be cloned.
+///
+/// ```
+/// # use genji::state::Resources;
+/// #[derive(Clone)]
+/// struct Score(u32);
+///
+/// let mut resources = Resources::new();
+/// resources.insert(Score(0));
+///
+/// resources.get_mut::<Score>().unwrap().0 += 10;
+/// assert_eq!(resources.get::<Score>().unwrap().0, 10);
+/// ```
+#[derive(Clone, Default)]
+pub struct Resources(HashMap<TypeId, Resource>);
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a resource, returning the previous one of the same type.
+    pub fn insert<R: Any + Clone>(&mut self, resource: R) -> Option<R> {
+        self.0
+            .insert(
+                TypeId::of::<R>(),
+                Resource {
+                    value: Box::new(resource),
+                    clone: clone_resource::<R>,
+                },
+            )
+            .and_then(|old| old.value.downcast().ok())
+            .map(|old| *old)
+    }
+
+    /// Returns a resource if it exists.
+    pub fn get<R: Any>(&self) -> Option<&R> {
+        self.0.get(&TypeId::of::<R>())?.value.downcast_ref()
+    }
+
+    /// Returns a resource mutably if it exists.
+    pub fn get_mut<R: Any>(&mut self) -> Option<&mut R> {
+        self.0.get_mut(&TypeId::of::<R>())?.value.downcast_mut()
+    }
+
+    /// Removes a resource, returning it if it exists.
+    pub fn remove<R: Any>(&mut self) -> Option<R> {
+        self.0
+            .remove(&TypeId::of::<R>())
+            .and_then(|old| old.value.downcast().ok())
+            .map(|old| *old)
+    }
+
+    /// Returns whether or not a resource exists.
+    pub fn contains<R: Any>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<R>())
+    }
+}
+
+impl Debug for Resources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resources")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
+    borrow::Borrow,
     collections::HashMap,
+    hash::Hash,
     ops::{Deref, DerefMut},
 };
 
@@ -65,6 +67,30 @@ impl<T: Clone> Store<T> {
     pub fn remove<I: ToString>(&mut self, id: I) -> Option<T> {
         self.0.remove(&id.to_string())
     }
+
+    /// Returns every name, in sorted order, unlike iterating
+    /// the underlying `HashMap` (e.g. for stable debug listings).
+    ///
+    /// ```
+    /// # use genji::store::Store;
+    /// let store = Store::new().with("b", 2).with("a", 1);
+    ///
+    /// assert_eq!(store.keys_sorted(), ["a", "b"]);
+    /// ```
+    pub fn keys_sorted(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.0.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Returns every name and item, sorted by name, unlike
+    /// iterating the underlying `HashMap` (e.g. for save files
+    /// that should be the same every run).
+    pub fn iter_sorted(&self) -> Vec<(&str, &T)> {
+        let mut items: Vec<(&str, &T)> = self.0.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        items.sort_unstable_by_key(|&(k, _)| k);
+        items
+    }
 }
 
 impl<T: Clone> Deref for Store<T> {
@@ -86,3 +112,77 @@ impl<T: Clone> Default for Store<T> {
         Self::new()
     }
 }
+
+/// Like a [`Store`], but keyed by any hashable type (such as
+/// an enum of asset ids) instead of strings.
+///
+/// ```
+/// # use genji::store::KeyedStore;
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Sfx {
+///     Jump,
+///     Coin,
+/// }
+///
+/// let store = KeyedStore::new().with(Sfx::Jump, "jump.ogg");
+///
+/// assert_eq!(store.get(&Sfx::Jump), Some("jump.ogg"));
+/// assert!(store.get(&Sfx::Coin).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyedStore<K: Hash + Eq, T: Clone>(HashMap<K, T>);
+
+impl<K: Hash + Eq, T: Clone> KeyedStore<K, T> {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Store an item in a builder pattern.
+    pub fn with(mut self, id: K, item: T) -> Self {
+        self.0.insert(id, item);
+        self
+    }
+
+    /// Store an item.
+    pub fn add(&mut self, id: K, item: T) {
+        self.0.insert(id, item);
+    }
+
+    /// Returns an item if it exists.
+    pub fn get<Q>(&self, id: &Q) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.get(id).cloned()
+    }
+
+    /// Remove an item, returning it if it exists.
+    pub fn remove<Q>(&mut self, id: &Q) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.remove(id)
+    }
+}
+
+impl<K: Hash + Eq, T: Clone> Deref for KeyedStore<K, T> {
+    type Target = HashMap<K, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K: Hash + Eq, T: Clone> DerefMut for KeyedStore<K, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Hash + Eq, T: Clone> Default for KeyedStore<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,273 @@
+//! A minimal immediate-mode UI: buttons, labels, and sliders.
+//!
+//! Widgets don't need entities. Call `show` on them every
+//! frame, in `onloop`, and they queue themselves to be drawn
+//! (over every sprite, like [`GameState::draw_rect`]) and
+//! report how the mouse interacted with them this frame.
+//!
+//! ```
+//! # use genji::{graphics, prelude::*, ui::{Button, Label, Slider, Style}};
+//! # fn dummy(state: &mut GameState<f32>) -> Option<()> {
+//! let style = Style::new(graphics::load_font_from_file("font.ttf")?);
+//!
+//! Label::new("Volume", &style).show(state, Point(0, 60));
+//!
+//! let mut volume = **state;
+//! Slider::new(120, 0.0, 1.0, &style).show(state, Point(0, 30), &mut volume);
+//! **state = volume;
+//!
+//! if Button::new("Play", (80, 24), &style).show(state, Point(0, 0)).clicked {
+//!     // Start the game...
+//! }
+//! # None
+//! # }
+//! ```
+//!
+//! Like all queued drawing, widgets are moved by
+//! `GameState.render_offset`; they're hit-tested where
+//! they're drawn, so they still line up with the mouse.
+//! Their sizes (and text) are scaled by `GameState.ui_scale`.
+//!
+//! Widgets are also drawn through `GameState.render_pass` and
+//! `snap_to_grid`, but hit-tested without them, so a render
+//! pass that moves sprites (or a coarse grid) can make widgets
+//! react where they *were*, not where they're shown.
+
+use ab_glyph::FontArc;
+
+use crate::{
+    graphics::{sprite, Color},
+    input::Key,
+    shape::{Contains, Point, Rect},
+    state::GameState,
+};
+
+/// The look of every widget. Cheap to clone.
+///
+/// A style without a font (like [`Style::default`]) draws
+/// widgets without their text, e.g. for a bare slider.
+#[derive(Debug, Clone)]
+pub struct Style {
+    /// The font of labels and of text on buttons.
+    pub font: Option<FontArc>,
+    /// The size of labels and of text on buttons.
+    /// Defaults to `24.0`.
+    pub font_size: f32,
+    /// The color of labels and of text on buttons.
+    /// Defaults to white.
+    pub text_color: Color,
+    /// The color of buttons and of slider tracks.
+    /// Defaults to dark gray.
+    pub color: Color,
+    /// The color of a widget while the mouse is over it.
+    /// Defaults to gray.
+    pub hover_color: Color,
+    /// The color of a pressed button, and of slider handles.
+    /// Defaults to light gray.
+    pub active_color: Color,
+}
+
+impl Style {
+    /// Creates a style with the given font, and defaults
+    /// for everything else. See each property for defaults.
+    pub fn new(font: FontArc) -> Self {
+        Self {
+            font: Some(font),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Style {
+    /// A style without a font. See each property for defaults.
+    fn default() -> Self {
+        Self {
+            font: None,
+            font_size: 24.0,
+            text_color: Color::new(255, 255, 255, 255),
+            color: Color::new(60, 60, 60, 255),
+            hover_color: Color::new(90, 90, 90, 255),
+            active_color: Color::new(140, 140, 140, 255),
+        }
+    }
+}
+
+/// How the mouse interacted with a widget this frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Response {
+    /// Whether or not the mouse is over the widget.
+    pub hovered: bool,
+    /// Whether or not the widget is being pressed (or,
+    /// for a slider, dragged).
+    pub held: bool,
+    /// Whether or not the widget was clicked this frame.
+    pub clicked: bool,
+    /// Whether or not a slider's value changed this frame.
+    pub changed: bool,
+}
+
+/// A line of text. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Label<'a> {
+    text: &'a str,
+    style: &'a Style,
+}
+
+impl<'a> Label<'a> {
+    /// Creates a label showing `text`.
+    pub fn new(text: &'a str, style: &'a Style) -> Self {
+        Self { text, style }
+    }
+
+    /// Draws the label, centered on `pos`, for this frame.
+    /// Draws nothing if the style has no font.
+    pub fn show<T>(&self, state: &mut GameState<T>, pos: Point) {
+        let Some(font) = self.style.font.clone() else {
+            return;
+        };
+
+        let text = sprite::text_with_font(self.text, font, self.style.font_size);
+        state.draw_text(&text, pos, self.style.text_color);
+    }
+}
+
+/// A clickable box with a label. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Button<'a> {
+    label: &'a str,
+    size: (i32, i32),
+    style: &'a Style,
+}
+
+impl<'a> Button<'a> {
+    /// Creates a button `size.0` by `size.1` coordinates big.
+    pub fn new(label: &'a str, size: (i32, i32), style: &'a Style) -> Self {
+        Self { label, size, style }
+    }
+
+    /// Draws the button, centered on `pos`, for this frame. It's
+    /// clicked on the frame the left mouse button is pressed over it.
+    pub fn show<T>(&self, state: &mut GameState<T>, pos: Point) -> Response {
+        let rect = Rect {
+            w: scaled(state, self.size.0),
+            h: scaled(state, self.size.1),
+        };
+
+        let hovered = hovering(state, &rect, pos);
+        let response = Response {
+            hovered,
+            held: hovered && state.keys[Key::LClick],
+            clicked: hovered && state.pressed[Key::LClick],
+            changed: false,
+        };
+
+        let color = if response.held {
+            self.style.active_color
+        } else if hovered {
+            self.style.hover_color
+        } else {
+            self.style.color
+        };
+
+        state.draw_rect(rect, pos, color);
+        Label::new(self.label, self.style).show(state, pos);
+
+        response
+    }
+}
+
+/// A horizontal slider between two values. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Slider<'a> {
+    width: i32,
+    min: f32,
+    max: f32,
+    style: &'a Style,
+}
+
+impl<'a> Slider<'a> {
+    /// The size of the handle, in coordinates. The slider
+    /// can be grabbed anywhere within the handle's height.
+    const HANDLE: (i32, i32) = (8, 16);
+    /// The thickness of the track, in coordinates.
+    const TRACK: i32 = 4;
+
+    /// Creates a slider `width` coordinates wide, for values
+    /// from `min` to `max`.
+    pub fn new(width: i32, min: f32, max: f32, style: &'a Style) -> Self {
+        Self {
+            width,
+            min,
+            max,
+            style,
+        }
+    }
+
+    /// Draws the slider, centered on `pos`, for this frame, and
+    /// updates `value` while it's dragged. A drag has to start
+    /// on the slider, but can then leave it.
+    pub fn show<T>(&self, state: &mut GameState<T>, pos: Point, value: &mut f32) -> Response {
+        let width = scaled(state, self.width);
+        let handle = Rect {
+            w: scaled(state, Self::HANDLE.0),
+            h: scaled(state, Self::HANDLE.1),
+        };
+        let area = Rect {
+            w: width,
+            h: handle.h,
+        };
+
+        let hovered = hovering(state, &area, pos);
+        let origin = pos + state.render_offset;
+        let held = state.keys[Key::LClick]
+            && state
+                .drag_start
+                .is_some_and(|start| area.contains(origin, start, 0.0));
+
+        let old = *value;
+        if held && width > 0 {
+            let left = origin.0 - width / 2;
+            let t = ((state.mouse_x - left) as f32 / width as f32).clamp(0.0, 1.0);
+            *value = self.min + t * (self.max - self.min);
+        }
+
+        let track = Rect {
+            w: width,
+            h: scaled(state, Self::TRACK),
+        };
+        let color = if hovered || held {
+            self.style.hover_color
+        } else {
+            self.style.color
+        };
+        state.draw_rect(track, pos, color);
+
+        let range = self.max - self.min;
+        let t = if range == 0.0 {
+            0.0
+        } else {
+            ((*value - self.min) / range).clamp(0.0, 1.0)
+        };
+        let x = pos.0 - width / 2 + (t * width as f32).round() as i32;
+        state.draw_rect(handle, Point(x, pos.1), self.style.active_color);
+
+        Response {
+            hovered,
+            held,
+            clicked: hovered && state.pressed[Key::LClick],
+            changed: *value != old,
+        }
+    }
+}
+
+/// Whether or not the mouse is over `rect` centered on
+/// `pos`, where it'll be drawn.
+fn hovering<T>(state: &GameState<T>, rect: &Rect, pos: Point) -> bool {
+    let mouse = Point(state.mouse_x, state.mouse_y);
+    state.mouse_in_window && rect.contains(pos + state.render_offset, mouse, 0.0)
+}
+
+/// Scales a size by `state.ui_scale`.
+fn scaled<T>(state: &GameState<T>, size: i32) -> i32 {
+    (size as f32 * state.ui_scale).round() as i32
+}
@@ -0,0 +1,355 @@
+//! Miscellaneous utilities for writing games.
+//!
+//! Provides [`Rng`], a small seedable random number
+//! generator, [`Cooldown`] and [`Timer`] for timing
+//! gameplay off of `GameState.delta`, and [`Camera`]
+//! for smoothly following the player.
+//!
+//! ```
+//! # use genji::{prelude::*, util::Rng};
+//!
+//! let mut rng = Rng::new(1234);
+//!
+//! let damage = rng.gen_range(5..10);
+//! let crit = rng.gen_bool(0.1);
+//!
+//! let spawn = rng.point_in(Point(0, 0), &shape::rect(200, 100));
+//! ```
+
+use std::{
+    ops::Range,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::shape::{Point, Rect};
+
+/// A small, fast, seedable pseudo-random number generator
+/// (xorshift64*).
+///
+/// Given the same seed, an `Rng` always produces the same
+/// sequence of numbers. It is *not* suitable for cryptography.
+///
+/// ```
+/// # use genji::util::Rng;
+///
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+///
+/// assert_eq!(a.gen_range(0..100), b.gen_range(0..100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        // Xorshift gets stuck on zero, so scramble the seed
+        // (splitmix64) to make sure that never happens.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Self(if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z })
+    }
+
+    /// Creates a new generator seeded from the system clock.
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Self::new(seed)
+    }
+
+    /// Returns a random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random `f32` in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a random number in `range` (excluding the end).
+    ///
+    /// Panics if the range is empty.
+    pub fn gen_range<T: SampleRange>(&mut self, range: Range<T>) -> T {
+        T::sample(self, range)
+    }
+
+    /// Returns `true` with a probability of `p` (0.0-1.0).
+    pub fn gen_bool(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// Returns a random point inside `rect`, centered on `pos`.
+    pub fn point_in(&mut self, pos: Point, rect: &Rect) -> Point {
+        let w = rect.w / 2;
+        let h = rect.h / 2;
+
+        Point(
+            self.gen_range(pos.0 - w..pos.0 + w + 1),
+            self.gen_range(pos.1 - h..pos.1 + h + 1),
+        )
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::from_time()
+    }
+}
+
+/// A type that [`Rng::gen_range`] can produce.
+pub trait SampleRange: Sized {
+    fn sample(rng: &mut Rng, range: Range<Self>) -> Self;
+}
+
+macro_rules! sample_int {
+    ( $( $ty:ty ),* ) => {$(
+        impl SampleRange for $ty {
+            fn sample(rng: &mut Rng, range: Range<Self>) -> Self {
+                assert!(range.start < range.end, "cannot sample an empty range");
+
+                let span = range.end.abs_diff(range.start) as u64;
+                range.start.wrapping_add((rng.next_u64() % span) as $ty)
+            }
+        }
+    )*};
+}
+
+sample_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl SampleRange for f32 {
+    fn sample(rng: &mut Rng, range: Range<Self>) -> Self {
+        assert!(range.start < range.end, "cannot sample an empty range");
+
+        range.start + (range.end - range.start) * rng.next_f32()
+    }
+}
+
+/// A cooldown, e.g. for limiting how often a player can shoot.
+/// Driven by `GameState.delta` (in milliseconds).
+///
+/// ```
+/// # use genji::util::Cooldown;
+/// let mut cooldown = Cooldown::new();
+/// assert!(cooldown.ready());
+///
+/// // Can't shoot again for 300ms.
+/// cooldown.start(300);
+/// assert!(!cooldown.ready());
+///
+/// cooldown.tick(300);
+/// assert!(cooldown.ready());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cooldown {
+    remaining: u128,
+}
+
+impl Cooldown {
+    /// Creates a cooldown that's ready immediately.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (Re)starts the cooldown, making it ready after `ms` milliseconds.
+    pub fn start(&mut self, ms: u128) {
+        self.remaining = ms;
+    }
+
+    /// Advances the cooldown by `delta` milliseconds.
+    pub fn tick(&mut self, delta: u128) {
+        self.remaining = self.remaining.saturating_sub(delta);
+    }
+
+    /// Returns whether or not the cooldown is over.
+    pub fn ready(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Returns how many milliseconds are left.
+    pub fn remaining(&self) -> u128 {
+        self.remaining
+    }
+}
+
+/// A timer that fires repeatedly, every `interval` milliseconds.
+/// Driven by `GameState.delta` (in milliseconds).
+///
+/// Time left over after firing carries into the next interval,
+/// so the timer doesn't drift.
+///
+/// ```
+/// # use genji::util::Timer;
+/// let mut timer = Timer::new(100);
+///
+/// assert_eq!(timer.tick(60), 0);
+/// assert_eq!(timer.tick(60), 1);
+/// assert_eq!(timer.tick(250), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timer {
+    interval: u128,
+    elapsed: u128,
+}
+
+impl Timer {
+    /// Creates a timer that fires every `interval` milliseconds.
+    ///
+    /// Panics if `interval` is `0`.
+    pub fn new(interval: u128) -> Self {
+        assert!(interval > 0, "timer interval must be positive");
+
+        Self {
+            interval,
+            elapsed: 0,
+        }
+    }
+
+    /// Advances the timer by `delta` milliseconds, returning
+    /// how many times it fired.
+    pub fn tick(&mut self, delta: u128) -> u32 {
+        self.elapsed += delta;
+        let fired = self.elapsed / self.interval;
+        self.elapsed %= self.interval;
+
+        fired as u32
+    }
+
+    /// Resets the timer without firing.
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+
+    /// Returns the interval of the timer, in milliseconds.
+    pub fn interval(&self) -> u128 {
+        self.interval
+    }
+}
+
+/// A camera that smoothly follows a target, driven by
+/// `GameState.delta` (in milliseconds). Apply it by setting
+/// `GameState.render_offset` to [`Camera::offset`].
+///
+/// ```
+/// # use genji::{prelude::*, util::Camera};
+/// # let mut state = GameState::new((), "", None, None, None, None);
+/// # let player = Point(0, 0);
+/// let mut camera = Camera::new(Point(0, 0));
+/// camera.deadzone = Some(shape::rect(40, 20));
+///
+/// // In onloop...
+/// camera.follow(player, state.delta, 0.05);
+/// state.render_offset = camera.offset();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    /// The point at the center of the screen. Kept
+    /// as floats so that slow movement isn't lost.
+    pub x: f32,
+    pub y: f32,
+    /// An area around the center of the screen in which
+    /// the target can move without the camera following.
+    /// Defaults to None.
+    pub deadzone: Option<Rect>,
+    /// The level (a rect centered on a point), which the
+    /// view is kept inside of. Defaults to None.
+    pub bounds: Option<(Point, Rect)>,
+    /// The size of the visible area, for `bounds`.
+    /// Defaults to `rect(400, 400)`; the screen is 400
+    /// coordinates tall (unless `GameState.virtual_size` is set).
+    pub view: Rect,
+}
+
+impl Camera {
+    /// Creates a camera centered on `pos`.
+    pub fn new(pos: Point) -> Self {
+        Self {
+            x: pos.0 as f32,
+            y: pos.1 as f32,
+            deadzone: None,
+            bounds: None,
+            view: Rect { w: 400, h: 400 },
+        }
+    }
+
+    /// Eases the camera toward `target` over `delta` milliseconds.
+    ///
+    /// `smoothing` is the fraction (`0.0`-`1.0`) of the distance
+    /// still left after one second: `0.0` snaps straight to the
+    /// target, and values closer to `1.0` follow more lazily.
+    pub fn follow(&mut self, target: Point, delta: u128, smoothing: f32) {
+        let (mut goal_x, mut goal_y) = (self.x, self.y);
+        let (dx, dy) = (target.0 as f32 - self.x, target.1 as f32 - self.y);
+
+        // Only move far enough to bring the target back to the
+        // edge of the deadzone.
+        let (half_w, half_h) = self.deadzone.map_or((0.0, 0.0), |zone| {
+            (zone.w as f32 / 2.0, zone.h as f32 / 2.0)
+        });
+        if dx.abs() > half_w {
+            goal_x += dx - half_w.copysign(dx);
+        }
+        if dy.abs() > half_h {
+            goal_y += dy - half_h.copysign(dy);
+        }
+
+        let t = 1.0 - smoothing.clamp(0.0, 1.0).powf(delta as f32 / 1000.0);
+        self.x += (goal_x - self.x) * t;
+        self.y += (goal_y - self.y) * t;
+
+        self.clamp();
+    }
+
+    /// Moves the camera straight to `pos` (within `bounds`).
+    pub fn snap(&mut self, pos: Point) {
+        self.x = pos.0 as f32;
+        self.y = pos.1 as f32;
+        self.clamp();
+    }
+
+    /// Keeps the view inside of `bounds`, centering it
+    /// on any axis where the level is smaller than the view.
+    fn clamp(&mut self) {
+        let Some((center, level)) = self.bounds else {
+            return;
+        };
+
+        let clamp_axis = |pos: f32, center: i32, level: i32, view: i32| {
+            let slack = (level - view) as f32 / 2.0;
+            if slack <= 0.0 {
+                center as f32
+            } else {
+                pos.clamp(center as f32 - slack, center as f32 + slack)
+            }
+        };
+
+        self.x = clamp_axis(self.x, center.0, level.w, self.view.w);
+        self.y = clamp_axis(self.y, center.1, level.h, self.view.h);
+    }
+
+    /// The position of the camera, rounded to coordinates.
+    pub fn pos(&self) -> Point {
+        Point(self.x.round() as i32, self.y.round() as i32)
+    }
+
+    /// The offset to draw the world with, for
+    /// `GameState.render_offset`.
+    pub fn offset(&self) -> Point {
+        Point(0, 0) - self.pos()
+    }
+
+    /// Converts a point on screen (e.g. the mouse)
+    /// into a point in the world.
+    pub fn to_world(&self, screen: Point) -> Point {
+        screen + self.pos()
+    }
+}
@@ -0,0 +1,73 @@
+use std::{io::Cursor, sync::Arc};
+
+use genji::prelude::*;
+use image::{Rgba, RgbaImage};
+
+/// A 4x2 image: red, then blue.
+fn image() -> Vec<u8> {
+    let image = RgbaImage::from_fn(4, 2, |x, _| {
+        if x < 2 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        }
+    });
+
+    let mut data = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    data
+}
+
+#[test]
+fn get_references_named_frames() {
+    let atlas = Atlas::new(
+        image(),
+        ImageFormat::Png,
+        [("red", (0, 0, 2, 2)), ("blue_strip", (2, 1, 2, 1))],
+    )
+    .unwrap();
+
+    let red = atlas.get("red", None, None).unwrap();
+    assert_eq!(red.region, Some((0, 0, 2, 2)));
+    assert_eq!((red.w, red.h), (2, 2));
+
+    let blue = atlas.get("blue_strip", None, Some(2)).unwrap();
+    assert_eq!(blue.region, Some((2, 1, 2, 1)));
+    assert_eq!((blue.w, blue.h), (4, 2));
+
+    assert!(Arc::ptr_eq(&red.data, &blue.data));
+    assert!(atlas.get("green", None, None).is_none());
+}
+
+#[test]
+fn rejects_frames_outside_the_image() {
+    assert!(Atlas::new(image(), ImageFormat::Png, [("wide", (1, 0, 4, 1))]).is_none());
+
+    let mut atlas = Atlas::new(image(), ImageFormat::Png, [("whole", (0, 0, 4, 2))]).unwrap();
+    assert!(!atlas.insert("tall", (0, 1, 1, 2)));
+    assert!(atlas.frame("tall").is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn load_from_ron() {
+    let dir = std::env::temp_dir();
+    let image_path = dir.join("genji_atlas_load.png");
+    let frames_path = dir.join("genji_atlas_load.ron");
+
+    std::fs::write(&image_path, image()).unwrap();
+    std::fs::write(
+        &frames_path,
+        r#"{ "red": (0, 0, 2, 2), "blue": (2, 0, 2, 2) }"#,
+    )
+    .unwrap();
+
+    let atlas = Atlas::load(&image_path, &frames_path).unwrap();
+    assert_eq!(atlas.frame("blue"), Some((2, 0, 2, 2)));
+
+    let mut names: Vec<_> = atlas.names().collect();
+    names.sort();
+    assert_eq!(names, ["blue", "red"]);
+}
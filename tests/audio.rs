@@ -0,0 +1,36 @@
+use genji::audio::{kira::sound::Region, AudioSettings, MusicSettings, SoundSettings};
+
+#[test]
+fn default_settings_match_kira() {
+    assert_eq!(
+        SoundSettings::from(AudioSettings::new()),
+        SoundSettings::new()
+    );
+    assert_eq!(
+        MusicSettings::from(AudioSettings::new()),
+        MusicSettings::new()
+    );
+}
+
+#[test]
+fn settings_carry_over() {
+    let settings = AudioSettings::new()
+        .volume(0.5)
+        .speed(1.2)
+        .panning(0.0)
+        .loop_region(2.5..);
+
+    let expected = SoundSettings::new()
+        .volume(0.5)
+        .playback_rate(1.2)
+        .panning(0.0)
+        .loop_region(2.5..);
+
+    assert_eq!(SoundSettings::from(settings), expected);
+}
+
+#[test]
+fn looping_covers_everything() {
+    let settings = AudioSettings::new().looping();
+    assert_eq!(settings.loop_region, Some(Region::from(..)));
+}
@@ -0,0 +1,148 @@
+use genji::graphics::Color;
+
+#[test]
+fn default_is_opaque_white() {
+    assert_eq!(Color::default(), Color::new(255, 255, 255, 255));
+}
+
+#[test]
+fn builder_matches_constructor() {
+    let built = Color::default().r(12).g(34).b(56).a(78);
+    assert_eq!(built, Color::new(12, 34, 56, 78));
+}
+
+#[test]
+fn to_f32_scales_channels() {
+    assert_eq!(Color::new(255, 0, 255, 0).to_f32(), [1.0, 0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn from_f32_scales_channels() {
+    assert_eq!(
+        Color::from_f32(1.0, 0.0, 1.0, 0.0),
+        Color::new(255, 0, 255, 0)
+    );
+}
+
+#[test]
+fn f32_round_trip() {
+    let color = Color::new(255, 128, 0, 255);
+    let [r, g, b, a] = color.to_f32();
+    let back = Color::from_f32(r, g, b, a);
+
+    assert_eq!(back.r, 255);
+    assert!(back.g.abs_diff(128) <= 1);
+    assert_eq!(back.b, 0);
+    assert_eq!(back.a, 255);
+}
+
+#[test]
+fn from_hex_accepts_every_form() {
+    let color = Color::new(0x12, 0x34, 0x56, 0xff);
+    assert_eq!(Color::from_hex("#123456"), Some(color));
+    assert_eq!(Color::from_hex("123456"), Some(color));
+    assert_eq!(Color::from_hex("#123456ff"), Some(color));
+    assert_eq!(
+        Color::from_hex("#abc"),
+        Some(Color::new(0xaa, 0xbb, 0xcc, 0xff))
+    );
+    assert_eq!(
+        Color::from_hex("#AbCdEf80"),
+        Some(Color::new(0xab, 0xcd, 0xef, 0x80))
+    );
+}
+
+#[test]
+fn from_hex_rejects_malformed_input() {
+    assert_eq!(Color::from_hex(""), None);
+    assert_eq!(Color::from_hex("#"), None);
+    assert_eq!(Color::from_hex("#12345"), None);
+    assert_eq!(Color::from_hex("#12345g"), None);
+    assert_eq!(Color::from_hex("#+12345"), None);
+    assert_eq!(Color::from_hex("##123456"), None);
+    assert_eq!(Color::from_hex("#12345é"), None);
+}
+
+#[test]
+fn hex_round_trip() {
+    let color = Color::new(0x01, 0xab, 0xff, 0x7f);
+    assert_eq!(color.to_hex(), "#01abff7f");
+    assert_eq!(Color::from_hex(&color.to_hex()), Some(color));
+}
+
+#[test]
+fn lerp_interpolates_and_rounds() {
+    let black = Color::new(0, 0, 0, 0);
+    let white = Color::new(255, 255, 255, 255);
+
+    assert_eq!(black.lerp(&white, 0.0), black);
+    assert_eq!(black.lerp(&white, 1.0), white);
+    assert_eq!(black.lerp(&white, 0.5), Color::new(128, 128, 128, 128));
+    assert_eq!(white.lerp(&black, 0.5), Color::new(128, 128, 128, 128));
+}
+
+#[test]
+fn lerp_clamps_t() {
+    let from = Color::new(10, 20, 30, 40);
+    let to = Color::new(200, 100, 0, 255);
+
+    assert_eq!(from.lerp(&to, -1.0), from);
+    assert_eq!(from.lerp(&to, 2.0), to);
+}
+
+#[test]
+fn named_constants() {
+    const RED: Color = Color::RED;
+
+    assert_eq!(Color::WHITE, Color::default());
+    assert_eq!(Color::BLACK, Color::new(0, 0, 0, 255));
+    assert_eq!(RED, Color::new(255, 0, 0, 255));
+    assert_eq!(Color::YELLOW, Color::RED.lerp(&Color::GREEN, 1.0).r(255));
+    assert_eq!(Color::TRANSPARENT.a, 0);
+}
+
+#[test]
+fn from_hsv_primaries() {
+    assert_eq!(Color::from_hsv(0.0, 1.0, 1.0, 255), Color::RED);
+    assert_eq!(Color::from_hsv(120.0, 1.0, 1.0, 255), Color::GREEN);
+    assert_eq!(Color::from_hsv(240.0, 1.0, 1.0, 255), Color::BLUE);
+    assert_eq!(Color::from_hsv(60.0, 1.0, 1.0, 128), Color::YELLOW.a(128));
+    assert_eq!(Color::from_hsv(0.0, 0.0, 0.0, 255), Color::BLACK);
+}
+
+#[test]
+fn from_hsv_wraps_hue() {
+    assert_eq!(Color::from_hsv(480.0, 1.0, 1.0, 255), Color::GREEN);
+    assert_eq!(Color::from_hsv(360.0, 1.0, 1.0, 255), Color::RED);
+    assert_eq!(Color::from_hsv(-60.0, 1.0, 1.0, 255), Color::MAGENTA);
+}
+
+#[test]
+fn from_hsl_matches_hsv() {
+    assert_eq!(Color::from_hsl(180.0, 1.0, 0.5, 255), Color::CYAN);
+    assert_eq!(Color::from_hsl(0.0, 1.0, 1.0, 255), Color::WHITE);
+    assert_eq!(
+        Color::from_hsl(0.0, 0.0, 0.5, 255),
+        Color::new(128, 128, 128, 255)
+    );
+}
+
+#[test]
+fn to_hsv_of_gray_has_no_hue() {
+    let (h, s, v) = Color::new(128, 128, 128, 255).to_hsv();
+    assert_eq!((h, s), (0.0, 0.0));
+    assert!((v - 128.0 / 255.0).abs() < 1e-6);
+}
+
+#[test]
+fn hsv_round_trip() {
+    for color in [
+        Color::new(255, 128, 0, 255),
+        Color::new(12, 34, 56, 78),
+        Color::MAGENTA,
+        Color::WHITE,
+    ] {
+        let (h, s, v) = color.to_hsv();
+        assert_eq!(Color::from_hsv(h, s, v, color.a), color);
+    }
+}
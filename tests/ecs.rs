@@ -0,0 +1,37 @@
+#![cfg(feature = "serde")]
+
+use genji::{ecs, prelude::*};
+
+#[test]
+fn save_and_load_world() {
+    let path = std::env::temp_dir().join("genji_save_and_load_world.ron");
+
+    let mut world = World::new();
+    let body = world.spawn((
+        shape::rect(20, 10),
+        Point(10, -5),
+        Color::new(255, 0, 0, 255),
+        Angle(45.0),
+        Fill(false),
+        StrokeJoin::Round,
+    ));
+    let arm = world.spawn((shape::circle(4), Point(0, 8), Parent(body)));
+
+    ecs::save_world(&world, &path).unwrap();
+    let loaded = ecs::load_world(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(*loaded.get::<&Point>(body).unwrap(), Point(10, -5));
+    assert_eq!(
+        *loaded.get::<&Color>(body).unwrap(),
+        Color::new(255, 0, 0, 255)
+    );
+    assert_eq!(**loaded.get::<&Angle>(body).unwrap(), 45.0);
+    assert!(!**loaded.get::<&Fill>(body).unwrap());
+    assert_eq!(*loaded.get::<&StrokeJoin>(body).unwrap(), StrokeJoin::Round);
+    assert_eq!(loaded.get::<&Rect>(body).unwrap().w, 20);
+
+    assert_eq!(loaded.get::<&Circle>(arm).unwrap().r, 4);
+    assert_eq!(**loaded.get::<&Parent>(arm).unwrap(), body);
+}
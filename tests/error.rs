@@ -0,0 +1,21 @@
+use std::sync::Mutex;
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record(message: &str) {
+    MESSAGES.lock().unwrap().push(message.to_string());
+}
+
+#[test]
+fn audio_errors_go_to_the_handler() {
+    genji::set_error_handler(record);
+
+    // Without an audio device, this reports an error. Reporting
+    // itself is tested in `helpers`, since nothing public always does.
+    let audio = genji::audio::Audio::new();
+    let messages = MESSAGES.lock().unwrap();
+    assert_eq!(messages.is_empty(), audio.is_available());
+    assert!(messages
+        .iter()
+        .all(|message| message.starts_with("failed to initialize audio")));
+}
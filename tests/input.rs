@@ -0,0 +1,206 @@
+use genji::input::{
+    Gamepad, GamepadAxis, GamepadButton, GamepadInput, GamepadMap, GamepadSide, InputBuffer, Key,
+    Keys,
+};
+use glium::glutin::event::VirtualKeyCode;
+
+#[test]
+fn virtual_combo_keeps_held_shift() {
+    let mut keys = Keys::new();
+    let mut modifiers = Keys::new();
+
+    // Hold shift...
+    modifiers[Key::Shift] = true;
+    keys[Key::Shift] = true;
+
+    // ...then type an asterisk.
+    let asterisk = Key::from_virtual(Some(VirtualKeyCode::Asterisk)).unwrap();
+    keys.set_virtual(&asterisk, true, &modifiers);
+    assert!(keys[Key::Shift]);
+    assert!(keys[Key::Eight]);
+
+    keys.set_virtual(&asterisk, false, &modifiers);
+    assert!(keys[Key::Shift]);
+    assert!(!keys[Key::Eight]);
+}
+
+#[test]
+fn virtual_combo_releases_synthetic_shift() {
+    let mut keys = Keys::new();
+    let modifiers = Keys::new();
+
+    let asterisk = Key::from_virtual(Some(VirtualKeyCode::Asterisk)).unwrap();
+    keys.set_virtual(&asterisk, true, &modifiers);
+    assert!(keys[Key::Shift]);
+
+    keys.set_virtual(&asterisk, false, &modifiers);
+    assert!(!keys[Key::Shift]);
+    assert!(!keys[Key::Eight]);
+}
+
+#[test]
+fn keys_index() {
+    let mut keys = Keys::new();
+    assert!(!keys[Key::Space]);
+
+    keys[Key::Space] = true;
+    assert!(keys[Key::Space]);
+    assert!(keys[Key::Space as usize]);
+}
+
+#[test]
+fn key_indices_round_trip() {
+    for (i, &key) in Key::ALL.iter().enumerate() {
+        assert_eq!(key as usize, i);
+        assert_eq!(Key::from(i), key);
+    }
+}
+
+#[test]
+fn key_arithmetic() {
+    assert_eq!(Key::M1 + 3u8, Key::M4);
+    assert_eq!(Key::M4 - 3u8, Key::M1);
+    assert_eq!(Key::A + 25u8, Key::Z);
+}
+
+#[test]
+#[should_panic]
+fn key_arithmetic_out_of_range() {
+    let _ = Key::M4 + 1u8;
+}
+
+#[test]
+fn all_and_any_down() {
+    let mut keys = Keys::new();
+    keys[Key::Ctrl] = true;
+
+    assert!(!keys.all_down(&[Key::Ctrl, Key::S]));
+    assert!(keys.any_down(&[Key::Ctrl, Key::S]));
+
+    keys[Key::S] = true;
+    assert!(keys.all_down(&[Key::Ctrl, Key::S]));
+
+    assert!(keys.all_down(&[]));
+    assert!(!keys.any_down(&[]));
+    assert!(!keys.any_down(&[Key::A, Key::B]));
+}
+
+#[test]
+fn gamepad_map_buttons_and_axes() {
+    let map = GamepadMap::arrows();
+    let mut gamepad = Gamepad::new();
+
+    gamepad.set_button(GamepadButton::South, true);
+    gamepad.set_axis(GamepadAxis::LeftStickX, -0.8);
+    gamepad.set_axis(GamepadAxis::LeftStickY, 0.3);
+
+    let keys = map.keys(&gamepad);
+    assert!(keys[Key::Space]);
+    assert!(keys[Key::Left]);
+    assert!(!keys[Key::Right]);
+    // Within the deadzone
+    assert!(!keys[Key::Up]);
+
+    gamepad.set_button(GamepadButton::South, false);
+    assert!(!map.keys(&gamepad)[Key::Space]);
+}
+
+#[test]
+fn gamepad_map_shares_keys() {
+    let mut map = GamepadMap::arrows();
+    let mut gamepad = Gamepad::new();
+
+    // Either the D-pad or the stick holds Up.
+    gamepad.set_button(GamepadButton::DPadUp, true);
+    gamepad.set_axis(GamepadAxis::LeftStickY, 1.0);
+    assert!(map.keys(&gamepad)[Key::Up]);
+
+    gamepad.set_axis(GamepadAxis::LeftStickY, 0.0);
+    assert!(map.keys(&gamepad)[Key::Up]);
+
+    map.unbind(GamepadInput::Button(GamepadButton::DPadUp));
+    assert!(!map.keys(&gamepad)[Key::Up]);
+}
+
+#[test]
+fn input_buffer_remembers_recent_presses() {
+    let mut buffer = InputBuffer::new();
+    let mut pressed = Keys::new();
+    pressed[Key::Space] = true;
+
+    buffer.update(&pressed, 16);
+    assert!(buffer.was_pressed_within(Key::Space, 0));
+
+    buffer.update(&Keys::new(), 100);
+    assert!(buffer.was_pressed_within(Key::Space, 100));
+    assert!(!buffer.was_pressed_within(Key::Space, 99));
+    assert!(!buffer.was_pressed_within(Key::Up, 1000));
+}
+
+#[test]
+fn input_buffer_consume_only_triggers_once() {
+    let mut buffer = InputBuffer::new();
+    let mut pressed = Keys::new();
+    pressed[Key::Z] = true;
+    buffer.update(&pressed, 16);
+
+    assert!(buffer.consume(Key::Z, 0));
+    assert!(!buffer.consume(Key::Z, 0));
+    assert!(!buffer.was_pressed_within(Key::Z, 1000));
+
+    // A stale press isn't consumed.
+    buffer.update(&pressed, 16);
+    buffer.update(&Keys::new(), 200);
+    assert!(!buffer.consume(Key::Z, 100));
+    assert!(buffer.was_pressed_within(Key::Z, 200));
+}
+
+#[test]
+fn input_buffer_clear_forgets_presses() {
+    let mut buffer = InputBuffer::new();
+    let mut pressed = Keys::new();
+    pressed[Key::A] = true;
+    buffer.update(&pressed, 16);
+
+    buffer.clear();
+    assert!(!buffer.was_pressed_within(Key::A, 1000));
+}
+
+#[test]
+fn gamepad_stick_deadzone_is_radial() {
+    let mut gamepad = Gamepad::new();
+    gamepad.stick_deadzone = 0.2;
+
+    // Resting inside the deadzone reads exactly zero.
+    gamepad.set_axis(GamepadAxis::LeftStickX, 0.1);
+    gamepad.set_axis(GamepadAxis::LeftStickY, -0.1);
+    assert_eq!(gamepad.left_stick(), (0.0, 0.0));
+
+    // Past it, the direction is kept and the length rescaled.
+    gamepad.set_axis(GamepadAxis::LeftStickX, 0.3);
+    gamepad.set_axis(GamepadAxis::LeftStickY, 0.4);
+    let (x, y) = gamepad.left_stick();
+    assert!((x.hypot(y) - 0.375).abs() < 1e-6);
+    assert!((x / y - 0.75).abs() < 1e-6);
+
+    // Diagonals don't go past full tilt.
+    gamepad.set_axis(GamepadAxis::RightStickX, 1.0);
+    gamepad.set_axis(GamepadAxis::RightStickY, 1.0);
+    let (x, y) = gamepad.right_stick();
+    assert!((x.hypot(y) - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn gamepad_trigger_deadzone() {
+    let mut gamepad = Gamepad::new();
+    gamepad.trigger_deadzone = 0.1;
+
+    gamepad.set_axis(GamepadAxis::LeftTrigger, 0.05);
+    assert_eq!(gamepad.trigger(GamepadSide::Left), 0.0);
+
+    gamepad.set_axis(GamepadAxis::RightTrigger, 0.55);
+    assert!((gamepad.trigger(GamepadSide::Right) - 0.5).abs() < 1e-6);
+
+    gamepad.set_axis(GamepadAxis::RightTrigger, 1.0);
+    assert_eq!(gamepad.trigger(GamepadSide::Right), 1.0);
+}
@@ -0,0 +1,22 @@
+use genji::loading::Progress;
+
+#[test]
+fn progress_is_shared_between_clones() {
+    let progress = Progress::new();
+    let worker = progress.clone();
+
+    std::thread::spawn(move || worker.set(0.25)).join().unwrap();
+    assert_eq!(progress.get(), 0.25);
+}
+
+#[test]
+fn progress_is_clamped() {
+    let progress = Progress::new();
+    assert_eq!(progress.get(), 0.0);
+
+    progress.set(2.0);
+    assert_eq!(progress.get(), 1.0);
+
+    progress.set(-1.0);
+    assert_eq!(progress.get(), 0.0);
+}
@@ -0,0 +1,122 @@
+use genji::{
+    prelude::*,
+    scene::{Scene, SceneStack, Transition},
+};
+
+/// Records what happened to each scene, in order.
+type Log = Vec<String>;
+
+#[derive(Clone)]
+struct Named(&'static str, fn() -> Transition<Log>);
+
+impl Scene<Log> for Named {
+    fn on_enter(&mut self, state: &mut GameState<Log>, _: &mut World) {
+        state.push(format!("enter {}", self.0));
+    }
+
+    fn on_exit(&mut self, state: &mut GameState<Log>, _: &mut World) {
+        state.push(format!("exit {}", self.0));
+    }
+
+    fn update(
+        &mut self,
+        state: &mut GameState<Log>,
+        _: &mut World,
+        _: &mut Audio,
+    ) -> Transition<Log> {
+        state.push(format!("update {}", self.0));
+        (self.1)()
+    }
+}
+
+fn stay() -> Transition<Log> {
+    Transition::None
+}
+
+fn pop() -> Transition<Log> {
+    Transition::Pop
+}
+
+fn pause() -> Transition<Log> {
+    Transition::Push(Box::new(Named("pause", pop)))
+}
+
+fn next_level() -> Transition<Log> {
+    Transition::Switch(Box::new(Named("level", stay)))
+}
+
+fn setup() -> (GameState<Log>, World, Audio) {
+    (
+        GameState::new(Vec::new(), "", None, None, None, None),
+        World::new(),
+        Audio::new(),
+    )
+}
+
+#[test]
+fn empty_stack_does_nothing() {
+    let (mut state, mut world, mut audio) = setup();
+    let mut scenes = SceneStack::new();
+
+    assert!(!scenes.update(&mut state, &mut world, &mut audio));
+    assert!(state.is_empty());
+}
+
+#[test]
+fn push_and_pop_resume_the_scene_below() {
+    let (mut state, mut world, mut audio) = setup();
+    let mut scenes = SceneStack::new();
+    scenes.push(Box::new(Named("game", pause)));
+
+    assert!(!scenes.update(&mut state, &mut world, &mut audio));
+    assert_eq!(scenes.len(), 2);
+    assert!(!scenes.update(&mut state, &mut world, &mut audio));
+    assert_eq!(scenes.len(), 1);
+
+    assert_eq!(
+        *state,
+        [
+            "enter game",
+            "update game",
+            "enter pause",
+            "update pause",
+            "exit pause"
+        ],
+    );
+}
+
+#[test]
+fn popping_the_last_scene_quits() {
+    let (mut state, mut world, mut audio) = setup();
+    let mut scenes = SceneStack::new();
+    scenes.push(Box::new(Named("menu", pop)));
+
+    assert!(scenes.update(&mut state, &mut world, &mut audio));
+    assert!(scenes.is_empty());
+}
+
+#[test]
+fn switch_clears_unkept_entities() {
+    let (mut state, mut world, mut audio) = setup();
+    let mut scenes = SceneStack::new();
+    scenes.push(Box::new(Named("menu", next_level)));
+
+    world.spawn((Point(0, 0),));
+    let ui = world.spawn((Point(0, 0), genji::ecs::Keep));
+
+    assert!(!scenes.update(&mut state, &mut world, &mut audio));
+    assert_eq!(world.len(), 1);
+    assert!(world.contains(ui));
+    assert_eq!(
+        *state,
+        ["enter menu", "update menu", "exit menu", "enter level"]
+    );
+}
+
+#[test]
+fn cloned_stack_keeps_its_scenes() {
+    let mut scenes: SceneStack<Log> = SceneStack::new();
+    scenes.push(Box::new(Named("menu", stay)));
+
+    assert_eq!(scenes.clone().len(), 1);
+}
@@ -0,0 +1,149 @@
+use genji::prelude::*;
+
+#[test]
+fn point_arithmetic() {
+    assert_eq!(Point(1, 2) + Point(3, 4), Point(4, 6));
+    assert_eq!(Point(1, 2) - Point(3, 4), Point(-2, -2));
+    assert_eq!(Point(1, 2) * 3, Point(3, 6));
+    assert_eq!(Point(9, 6) / 3, Point(3, 2));
+}
+
+#[test]
+fn point_saturating_arithmetic() {
+    let max = Point(i32::MAX, i32::MAX);
+    let min = Point(i32::MIN, i32::MIN);
+
+    assert_eq!(
+        max.saturating_add(Point(1, -1)),
+        Point(i32::MAX, i32::MAX - 1)
+    );
+    assert_eq!(
+        min.saturating_sub(Point(1, -1)),
+        Point(i32::MIN, i32::MIN + 1)
+    );
+    assert_eq!(max.saturating_mul(2), max);
+    assert_eq!(max.saturating_mul(-2), min);
+    assert_eq!(Point(3, -4).saturating_mul(2), Point(3, -4) * 2);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn point_add_overflow_panics_in_debug() {
+    let _ = Point(i32::MAX, 0) + Point(1, 0);
+}
+
+#[test]
+fn point_cross_product() {
+    assert_eq!(Point(1, 0) * Point(0, 1), 1);
+    assert_eq!(Point(0, 1) * Point(1, 0), -1);
+}
+
+#[test]
+fn point_len_and_norm() {
+    assert_eq!(Point(3, 4).len(), 5.0);
+    assert_eq!(Point(3, 4).norm(), (0.6, 0.8));
+}
+
+#[test]
+fn point_ordering() {
+    assert!(Point(0, 0) < Point(1, 1));
+    assert!(Point(1, 1) > Point(0, 0));
+    assert_eq!(Point(0, 1).partial_cmp(&Point(1, 0)), None);
+}
+
+#[test]
+fn fpoint_rounding() {
+    assert_eq!(FPoint(1.4, -2.6).round(), Point(1, -3));
+    assert_eq!(FPoint(1.4, -2.6).floor(), Point(1, -3));
+    assert_eq!(FPoint(0.5, 0.25).floor(), Point(0, 0));
+    assert_eq!(FPoint::from(Point(3, -4)), FPoint(3.0, -4.0));
+    assert_eq!(FPoint(1.5, 2.0) - FPoint(0.5, 0.5), FPoint(1.0, 1.5));
+}
+
+#[test]
+fn circle_contains() {
+    let circle = shape::circle(10);
+    let pos = Point(100, 100);
+
+    assert!(circle.contains(pos, Point(100, 100), 0.0));
+    assert!(circle.contains(pos, Point(105, 95), 0.0));
+    assert!(!circle.contains(pos, Point(111, 100), 0.0));
+    assert!(circle.contains(pos, Point(105, 95), 123.0));
+}
+
+#[test]
+fn rect_contains() {
+    let rect = shape::rect(20, 10);
+    let pos = Point(0, 0);
+
+    assert!(rect.contains(pos, Point(0, 0), 0.0));
+    assert!(rect.contains(pos, Point(10, 5), 0.0));
+    assert!(!rect.contains(pos, Point(11, 0), 0.0));
+    assert!(!rect.contains(pos, Point(0, 6), 0.0));
+
+    // Rotated a quarter turn, the rect is tall instead of wide.
+    assert!(!rect.contains(pos, Point(9, 0), 90.0));
+    assert!(rect.contains(pos, Point(0, 9), 90.0));
+}
+
+#[test]
+fn rotated_rect_contains_matches_rendering() {
+    let mut world = World::new();
+    let id = world.spawn((shape::rect(100, 20), Point(0, 0), Angle(45.0)));
+
+    let mut query = world.query_one::<(&Rect, &Point, &Angle)>(id).unwrap();
+    let (rect, pos, angle) = query.get().unwrap();
+
+    // A positive angle draws the rect turned clockwise, so its right
+    // end dips below the x axis; the corner at (50, 10) lands near
+    // (42, -28), and (38, -27) is just inside of it.
+    assert!(rect.contains(*pos, Point(34, -34), **angle));
+    assert!(rect.contains(*pos, Point(38, -27), **angle));
+
+    // The same points mirrored across the x axis are outside.
+    assert!(!rect.contains(*pos, Point(34, 34), **angle));
+    assert!(!rect.contains(*pos, Point(38, 27), **angle));
+}
+
+#[test]
+fn triangle_contains() {
+    let triangle = shape::triangle(20, 20, 0);
+    let pos = Point(0, 0);
+
+    assert!(triangle.contains(pos, Point(0, 0), 0.0));
+    assert!(triangle.contains(pos, Point(0, 9), 0.0));
+    assert!(!triangle.contains(pos, Point(0, 11), 0.0));
+    assert!(!triangle.contains(pos, Point(0, -11), 0.0));
+    assert!(!triangle.contains(pos, Point(8, 8), 0.0));
+
+    // Flipped upside-down, the tip points down.
+    assert!(triangle.contains(pos, Point(8, 8), 180.0));
+    assert!(!triangle.contains(pos, Point(8, -8), 180.0));
+}
+
+#[test]
+fn texture_contains() {
+    let texture = sprite::texture_raw(vec![0; 4 * 4 * 4], (4, 4), Some(20), Some(10));
+    let pos = Point(0, 0);
+
+    assert!(texture.contains(pos, Point(0, 0), 0.0));
+    assert!(texture.contains(pos, Point(9, 4), 0.0));
+    assert!(!texture.contains(pos, Point(11, 0), 0.0));
+}
+
+#[test]
+fn contains_transformed_scales_bounds() {
+    let pos = Point(50, 50);
+    let rect = shape::rect(20, 10);
+
+    assert!(!rect.contains(pos, Point(65, 50), 0.0));
+    assert!(rect.contains_transformed(pos, Point(65, 50), 0.0, (2.0, 1.0)));
+    assert!(!rect.contains_transformed(pos, Point(50, 58), 0.0, (2.0, 1.0)));
+    assert!(rect.contains_transformed(pos, Point(50, 58), 90.0, (2.0, 1.0)));
+
+    let circle = shape::circle(10);
+    assert!(circle.contains_transformed(pos, Point(65, 50), 0.0, (2.0, 2.0)));
+    assert!(!circle.contains_transformed(pos, Point(55, 50), 0.0, (0.5, 0.5)));
+    assert!(!circle.contains_transformed(pos, pos, 0.0, (0.0, 1.0)));
+}
@@ -0,0 +1,191 @@
+use genji::prelude::*;
+
+#[test]
+fn sprite_data_defaults() {
+    let ex = SpriteData::new();
+
+    assert_eq!((ex.x, ex.y), (0, 0));
+    assert_eq!(ex.depth, 1);
+    assert_eq!(ex.angle, 0.0);
+    assert!(ex.fill);
+    assert_eq!(ex.stroke_weight, 4);
+    assert_eq!(ex.join, StrokeJoin::Miter);
+    assert_eq!(ex.cap, StrokeCap::Butt);
+    assert_eq!(ex.color, Color::default());
+}
+
+#[test]
+fn sprite_data_builder() {
+    let ex = SpriteData::new()
+        .pos(Point(20, -30))
+        .angle(45.0)
+        .color(Color::new(255, 0, 0, 255))
+        .depth(3)
+        .fill(false)
+        .stroke_weight(8)
+        .join(StrokeJoin::Round)
+        .cap(StrokeCap::Round);
+
+    assert_eq!((ex.x, ex.y), (20, -30));
+    assert_eq!(ex.angle, 45.0);
+    assert_eq!(ex.color, Color::new(255, 0, 0, 255));
+    assert_eq!(ex.depth, 3);
+    assert!(!ex.fill);
+    assert_eq!(ex.stroke_weight, 8);
+    assert_eq!(ex.join, StrokeJoin::Round);
+    assert_eq!(ex.cap, StrokeCap::Round);
+}
+
+#[test]
+fn texture_pixel_access() {
+    let mut texture = sprite::texture_raw(vec![0; 2 * 2 * 4], (2, 2), None, None);
+
+    texture.set_pixel(1, 0, [255, 0, 0, 255]);
+    assert_eq!(texture.get_pixel(1, 0), Some([255, 0, 0, 255]));
+    assert_eq!(texture.get_pixel(0, 1), Some([0, 0, 0, 0]));
+    assert_eq!(&texture.data[4..8], &[255, 0, 0, 255]);
+
+    // Out of bounds
+    assert_eq!(texture.get_pixel(2, 0), None);
+    texture.set_pixel(0, 2, [1, 2, 3, 4]);
+
+    texture.fill([9, 9, 9, 9]);
+    assert!(texture.data.iter().all(|&b| b == 9));
+}
+
+#[test]
+fn texture_set_pixel_copies_shared_data() {
+    let original = sprite::texture_raw(vec![0; 4], (1, 1), None, None);
+    let mut copy = original.clone();
+
+    copy.set_pixel(0, 0, [1, 2, 3, 4]);
+    assert_eq!(copy.get_pixel(0, 0), Some([1, 2, 3, 4]));
+    assert_eq!(original.get_pixel(0, 0), Some([0, 0, 0, 0]));
+}
+
+#[test]
+fn sprite_bundle_spawns_components() {
+    let mut world = World::new();
+    let id = world.spawn(
+        shape::rect(20, 10)
+            .at(5, -5)
+            .color(Color::new(255, 0, 0, 255))
+            .depth(3)
+            .fill(false)
+            .build(),
+    );
+
+    assert_eq!(world.get::<&Rect>(id).unwrap().w, 20);
+    assert_eq!(*world.get::<&Point>(id).unwrap(), Point(5, -5));
+    assert_eq!(
+        *world.get::<&Color>(id).unwrap(),
+        Color::new(255, 0, 0, 255)
+    );
+    assert_eq!(*world.get::<&Depth>(id).unwrap(), Depth(3));
+    assert_eq!(*world.get::<&Fill>(id).unwrap(), Fill(false));
+    assert_eq!(*world.get::<&Angle>(id).unwrap(), Angle(0.0));
+}
+
+#[test]
+fn load_font_rejects_invalid_data() {
+    assert!(genji::graphics::load_font(b"not a font").is_none());
+    assert!(genji::graphics::load_font_from_file("does/not/exist.ttf").is_none());
+}
+
+#[test]
+fn texture_resize_keeps_aspect() {
+    let mut texture = sprite::texture_raw(vec![0; 20 * 10 * 4], (20, 10), None, None);
+    assert_eq!((texture.w, texture.h), (20, 10));
+
+    texture.resize(Some(40), None);
+    assert_eq!((texture.w, texture.h), (40, 20));
+
+    texture.resize(None, Some(5));
+    assert_eq!((texture.w, texture.h), (10, 5));
+
+    texture.resize(Some(7), Some(3));
+    assert_eq!((texture.w, texture.h), (7, 3));
+
+    // Relative to the region, not the whole image.
+    texture.region = Some((0, 0, 10, 10));
+    texture.resize(Some(30), None);
+    assert_eq!((texture.w, texture.h), (30, 30));
+
+    assert_eq!(texture.data.len(), 20 * 10 * 4);
+}
+
+#[test]
+fn texture_wrap_defaults() {
+    let texture = sprite::texture_raw(vec![0; 4], (1, 1), None, None);
+
+    assert_eq!(texture.wrap, sprite::Wrap::Clamp);
+    assert_eq!(texture.tiles, (1.0, 1.0));
+    assert_eq!(texture.scroll, (0.0, 0.0));
+}
+
+#[test]
+fn tiled_texture_repeats_across_region() {
+    let texture = sprite::texture_raw(vec![0; 16 * 16 * 4], (16, 16), Some(16), Some(16));
+    let wall = texture.tiled(shape::rect(400, 48));
+
+    assert_eq!((wall.w, wall.h), (400, 48));
+    assert_eq!(wall.tiles, (25.0, 3.0));
+    assert_eq!(wall.wrap, sprite::Wrap::Repeat);
+    assert_eq!(wall.region, None);
+
+    let mut twice = sprite::texture_raw(vec![0; 16 * 16 * 4], (16, 16), Some(16), Some(16));
+    twice.tiles = (2.0, 1.0);
+    assert_eq!(twice.tiled(shape::rect(32, 32)).tiles, (4.0, 2.0));
+}
+
+#[test]
+fn tiled_texture_copies_out_its_region() {
+    // Two 2x2 tiles side by side: black, then white.
+    let mut sheet = sprite::texture_raw(vec![0; 4 * 2 * 4], (4, 2), Some(2), Some(2));
+    for (x, y) in [(2, 0), (3, 0), (2, 1), (3, 1)] {
+        sheet.set_pixel(x, y, [255; 4]);
+    }
+    sheet.region = Some((2, 0, 2, 2));
+
+    let floor = sheet.tiled(shape::rect(8, 8));
+    assert_eq!(floor.region, None);
+    assert_eq!(floor.dimensions, (2, 2));
+    assert_eq!(floor.tiles, (4.0, 4.0));
+    assert!(floor.data.iter().all(|&byte| byte == 255));
+}
+
+#[test]
+fn snap_to_grid_rounds_to_the_nearest_multiple() {
+    let ex = SpriteData::new().fpos(FPoint(5.5, -6.4)).snap_to_grid(4);
+    assert_eq!((ex.x, ex.y), (4, -8));
+    assert_eq!(ex.frac, (0.0, 0.0));
+
+    let ex = SpriteData::new().pos(Point(6, 1)).snap_to_grid(4);
+    assert_eq!((ex.x, ex.y), (8, 0));
+
+    let ex = SpriteData::new().fpos(FPoint(1.25, 0.5)).snap_to_grid(0);
+    assert_eq!((ex.x, ex.y, ex.frac), (1, 0, (0.25, 0.5)));
+}
+
+#[test]
+fn texture_from_file_stores_the_canonical_path() {
+    let dir = std::env::temp_dir().join(format!("genji-canonical-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    image::RgbaImage::new(2, 2)
+        .save(dir.join("tile.png"))
+        .unwrap();
+
+    let roundabout = dir.join("sub").join("..").join("tile.png");
+    let texture = sprite::texture_from_file(roundabout.display(), None, None).unwrap();
+    assert_eq!(
+        texture.path,
+        Some(dir.join("tile.png").canonicalize().unwrap())
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn texture_from_bad_data_is_none() {
+    assert!(sprite::texture(vec![1, 2, 3], sprite::ImageFormat::Png, None, None).is_none());
+}
@@ -0,0 +1,160 @@
+use std::{io::Cursor, sync::Arc};
+
+use genji::prelude::*;
+use image::{Rgba, RgbaImage};
+
+/// A 4x2 image of two 2x2 tiles: red, then blue.
+fn two_tiles() -> Vec<u8> {
+    let image = RgbaImage::from_fn(4, 2, |x, _| {
+        if x < 2 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        }
+    });
+
+    let mut data = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    data
+}
+
+/// A 4x4 image of a 2x2 grid of 2x2 tiles.
+fn grid_of_tiles() -> Vec<u8> {
+    let image = RgbaImage::from_fn(4, 4, |x, y| Rgba([x as u8 * 60, y as u8 * 60, 0, 255]));
+
+    let mut data = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    data
+}
+
+#[test]
+fn rejects_uneven_tiles() {
+    assert!(Spritemap::new(two_tiles(), ImageFormat::Png, 3, 2).is_none());
+}
+
+#[test]
+fn get_id_references_tiles() {
+    let map = Spritemap::new(two_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    let red = map.get_id(0, None, None).unwrap();
+    assert_eq!(red.dimensions, (4, 2));
+    assert_eq!(red.region, Some((0, 0, 2, 2)));
+    assert_eq!((red.w, red.h), (2, 2));
+
+    let blue = map.get_id(1, Some(4), None).unwrap();
+    assert_eq!(blue.region, Some((2, 0, 2, 2)));
+    assert_eq!((blue.w, blue.h), (4, 4));
+
+    // Tiles share the spritemap's image instead of copying it.
+    assert!(Arc::ptr_eq(&red.data, &blue.data));
+}
+
+#[test]
+fn get_id_out_of_range() {
+    let map = Spritemap::new(two_tiles(), ImageFormat::Png, 2, 2).unwrap();
+    assert!(map.get_id(3, None, None).is_none());
+}
+
+#[test]
+fn get_rect_references_region() {
+    let map = Spritemap::new(two_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    let region = map.get_rect(1, 0, 2, 1, None, None).unwrap();
+    assert_eq!(region.region, Some((1, 0, 2, 1)));
+    assert_eq!((region.w, region.h), (2, 1));
+    assert_eq!(region.data.len(), 4 * 2 * 4);
+}
+
+#[test]
+fn get_rect_can_fill_the_map() {
+    let map = Spritemap::new(two_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    let whole = map.get_rect(0, 0, 4, 2, None, None).unwrap();
+    assert_eq!(whole.region, Some((0, 0, 4, 2)));
+
+    let corner = map.get_rect(3, 1, 1, 1, None, None).unwrap();
+    assert_eq!(corner.region, Some((3, 1, 1, 1)));
+}
+
+#[test]
+fn get_rect_out_of_range() {
+    let map = Spritemap::new(two_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    assert!(map.get_rect(0, 0, 5, 2, None, None).is_none());
+    assert!(map.get_rect(0, 1, 4, 2, None, None).is_none());
+    assert!(map.get_rect(u32::MAX, 0, 2, 1, None, None).is_none());
+}
+
+#[test]
+fn get_id_bounds_match_tile_count() {
+    let map = Spritemap::new(grid_of_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    for id in 0..4 {
+        assert!(map.get_id(id, None, None).is_some(), "tile {id} is missing");
+    }
+    assert_eq!(
+        map.get_id(3, None, None).unwrap().region,
+        Some((2, 2, 2, 2))
+    );
+    assert!(map.get_id(4, None, None).is_none());
+}
+
+#[test]
+fn animation_extracts_frames_in_order() {
+    let map = Spritemap::new(grid_of_tiles(), ImageFormat::Png, 2, 2).unwrap();
+
+    let frames = map.animation(&[3, 0, 1], None, None).unwrap();
+    let regions = frames.iter().map(|f| f.region).collect::<Vec<_>>();
+    assert_eq!(
+        regions,
+        [Some((2, 2, 2, 2)), Some((0, 0, 2, 2)), Some((2, 0, 2, 2))]
+    );
+
+    assert!(map.animation(&[0, 4], None, None).is_none());
+}
+
+#[test]
+fn sprite_animation_advances_and_loops() {
+    let map = Spritemap::new(grid_of_tiles(), ImageFormat::Png, 2, 2).unwrap();
+    let frames = map.animation(&[0, 1, 2], None, None).unwrap();
+    let mut animation = SpriteAnimation::new(frames, 100).unwrap();
+
+    assert_eq!(animation.frame(), 0);
+    animation.advance(99);
+    assert_eq!(animation.frame(), 0);
+    animation.advance(1);
+    assert_eq!(animation.frame(), 1);
+    assert_eq!(animation.current().region, Some((2, 0, 2, 2)));
+
+    animation.advance(250);
+    assert_eq!(animation.frame(), 0);
+
+    animation.reset();
+    animation.advance(1000);
+    assert_eq!(animation.frame(), 1);
+}
+
+#[test]
+fn sprite_animation_edge_cases() {
+    assert!(SpriteAnimation::new(Vec::new(), 100).is_none());
+
+    let map = Spritemap::new(grid_of_tiles(), ImageFormat::Png, 2, 2).unwrap();
+    let frames = map.animation(&[0, 1], None, None).unwrap();
+    let mut held = SpriteAnimation::new(frames, 0).unwrap();
+    held.advance(1000);
+    assert_eq!(held.frame(), 0);
+
+    held.frame_ms = 100;
+    held.advance(150);
+    assert_eq!(held.frame(), 1);
+    held.frame_ms = 0;
+    held.advance(1000);
+    assert_eq!(held.frame(), 1);
+
+    held.reset();
+    assert_eq!(held.frame(), 0);
+}
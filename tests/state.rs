@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use genji::prelude::*;
+
+#[test]
+fn fps_is_frames_per_second() {
+    let mut state = GameState::new((), "", None, None, Some(50), None);
+    assert_eq!(state.fps, 50);
+    assert_eq!(state.frame_time(), Duration::from_millis(20));
+
+    // Changing it at runtime keeps the same units.
+    state.fps = 100;
+    assert_eq!(state.frame_time(), Duration::from_millis(10));
+
+    state.fps = 0;
+    assert_eq!(state.frame_time(), Duration::ZERO);
+}
+
+#[test]
+fn uptime_counts_from_start_time() {
+    let mut state = GameState::new((), "", None, None, None, None);
+    state.start_time -= Duration::from_secs(5);
+    assert!(state.uptime() >= Duration::from_secs(5));
+}
+
+#[test]
+fn pixel_at_is_none_before_a_frame_is_drawn() {
+    let mut state = GameState::new((), "", None, None, None, None);
+    assert_eq!(state.pixel_at(0, 0), None);
+    assert_eq!(state.pixel_at(0, 0), None);
+}
+
+#[test]
+fn event_proxy_is_none_before_the_window_opens() {
+    let state = GameState::new((), "", None, None, None, None);
+    assert!(state.event_proxy().is_none());
+}
+
+#[test]
+fn window_mode_defaults_to_windowed() {
+    let state = GameState::new((), "", None, None, None, None);
+    assert_eq!(state.window_mode, WindowMode::Windowed);
+    assert!(state.video_modes().is_empty());
+}
+
+#[test]
+fn ui_scale_defaults_to_one() {
+    let state = GameState::new((), "", None, None, None, None);
+    assert_eq!(state.ui_scale, 1.0);
+    assert_eq!(state.scale_factor(), 1.0);
+    assert_eq!(SpriteData::new().text_scale, 1.0);
+}
+
+#[test]
+fn scrolling_defaults_to_unscaled_on_both_axes() {
+    let state = GameState::new((), "", None, None, None, None);
+    assert_eq!((state.scroll, state.scroll_x, state.scroll_y), (0, 0, 0));
+    assert_eq!(state.scroll_sensitivity, (1.0, 1.0));
+    assert_eq!(state.scroll_invert, (false, false));
+}
+
+#[test]
+fn close_on_request_defaults_to_true() {
+    let state = GameState::new((), "", None, None, None, None);
+    assert!(state.close_on_request);
+    assert!(!state.asked_to_close);
+}
@@ -0,0 +1,66 @@
+use genji::store::{KeyedStore, Store};
+
+#[test]
+fn add_get_remove() {
+    let mut store = Store::new();
+    store.add("one", 1);
+    store.add(2, 2);
+
+    assert_eq!(store.get("one"), Some(1));
+    assert_eq!(store.get("2"), Some(2));
+    assert_eq!(store.get("three"), None);
+
+    assert_eq!(store.remove("one"), Some(1));
+    assert_eq!(store.remove("one"), None);
+    assert_eq!(store.len(), 1);
+}
+
+#[test]
+fn with_builds() {
+    let store = Store::new().with("a", 'a').with("b", 'b');
+
+    assert_eq!(store.get("a"), Some('a'));
+    assert_eq!(store.get("b"), Some('b'));
+}
+
+#[test]
+fn add_overwrites() {
+    let mut store = Store::default();
+    store.add("item", 1);
+    store.add("item", 2);
+
+    assert_eq!(store.get("item"), Some(2));
+    assert_eq!(store.len(), 1);
+}
+
+#[test]
+fn sorted_iteration() {
+    let mut store = Store::new();
+    for name in ["zombie", "apple", "mango", "Banana"] {
+        store.add(name, name.len());
+    }
+
+    assert_eq!(store.keys_sorted(), ["Banana", "apple", "mango", "zombie"]);
+    assert_eq!(
+        store.iter_sorted(),
+        [("Banana", &6), ("apple", &5), ("mango", &5), ("zombie", &6)]
+    );
+}
+
+#[test]
+fn keyed_store_uses_keys_directly() {
+    let mut store = KeyedStore::new().with(1u32, 'a');
+    store.add(2, 'b');
+
+    assert_eq!(store.get(&1), Some('a'));
+    assert_eq!(store.get(&2), Some('b'));
+    assert_eq!(store.remove(&1), Some('a'));
+    assert_eq!(store.get(&1), None);
+    assert_eq!(store.len(), 1);
+}
+
+#[test]
+fn keyed_store_borrows_keys() {
+    let store = KeyedStore::new().with(String::from("name"), 5);
+    assert_eq!(store.get("name"), Some(5));
+}
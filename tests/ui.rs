@@ -0,0 +1,91 @@
+use genji::{
+    prelude::*,
+    ui::{Button, Slider, Style},
+};
+
+fn state() -> GameState<()> {
+    let mut state = GameState::new((), "", None, None, None, None);
+    state.mouse_in_window = true;
+    state
+}
+
+/// Holds the left mouse button at `mouse`, having pressed it at `start`.
+fn drag(state: &mut GameState<()>, start: Point, mouse: Point) {
+    state.keys[Key::LClick] = true;
+    state.drag_start = Some(start);
+    state.mouse_x = mouse.0;
+    state.mouse_y = mouse.1;
+}
+
+#[test]
+fn slider_maps_the_mouse_onto_its_range() {
+    let style = Style::default();
+    let slider = Slider::new(100, 10.0, 20.0, &style);
+    let mut state = state();
+    let mut value = 10.0;
+
+    drag(&mut state, Point(0, 0), Point(25, 0));
+    let response = slider.show(&mut state, Point(0, 0), &mut value);
+    assert!(response.held && response.changed);
+    assert_eq!(value, 17.5);
+
+    // Past either end, the value is clamped.
+    drag(&mut state, Point(0, 0), Point(-80, 0));
+    slider.show(&mut state, Point(0, 0), &mut value);
+    assert_eq!(value, 10.0);
+
+    drag(&mut state, Point(0, 0), Point(80, 0));
+    slider.show(&mut state, Point(0, 0), &mut value);
+    assert_eq!(value, 20.0);
+}
+
+#[test]
+fn slider_drags_must_start_on_it() {
+    let style = Style::default();
+    let slider = Slider::new(100, 0.0, 1.0, &style);
+    let mut state = state();
+    let mut value = 0.5;
+
+    drag(&mut state, Point(0, 100), Point(50, 0));
+    let response = slider.show(&mut state, Point(0, 0), &mut value);
+    assert!(!response.held && !response.changed);
+    assert_eq!(value, 0.5);
+
+    // Once it's started, the drag can leave the slider.
+    drag(&mut state, Point(0, 0), Point(50, 100));
+    let response = slider.show(&mut state, Point(0, 0), &mut value);
+    assert!(response.held && !response.hovered);
+    assert_eq!(value, 1.0);
+}
+
+#[test]
+fn button_is_hit_tested_where_its_drawn() {
+    let style = Style::default();
+    let button = Button::new("", (80, 24), &style);
+    let mut state = state();
+    state.render_offset = Point(200, 0);
+
+    state.mouse_x = 10;
+    assert!(!button.show(&mut state, Point(0, 0)).hovered);
+
+    state.mouse_x = 210;
+    state.pressed[Key::LClick] = true;
+    let response = button.show(&mut state, Point(0, 0));
+    assert!(response.hovered && response.clicked);
+
+    state.mouse_in_window = false;
+    assert!(!button.show(&mut state, Point(0, 0)).hovered);
+}
+
+#[test]
+fn widgets_scale_with_ui_scale() {
+    let style = Style::default();
+    let button = Button::new("", (80, 24), &style);
+    let mut state = state();
+    state.mouse_x = 60;
+
+    assert!(!button.show(&mut state, Point(0, 0)).hovered);
+
+    state.ui_scale = 2.0;
+    assert!(button.show(&mut state, Point(0, 0)).hovered);
+}
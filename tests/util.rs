@@ -0,0 +1,129 @@
+use genji::{prelude::*, util::*};
+
+#[test]
+fn rng_is_deterministic() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+
+    for _ in 0..100 {
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
+
+#[test]
+fn rng_zero_seed_works() {
+    let mut rng = Rng::new(0);
+    assert_ne!(rng.next_u64(), rng.next_u64());
+}
+
+#[test]
+fn rng_gen_range_in_bounds() {
+    let mut rng = Rng::new(7);
+
+    for _ in 0..1000 {
+        let i = rng.gen_range(-5..5);
+        assert!((-5..5).contains(&i));
+
+        let f = rng.gen_range(1.0..2.0);
+        assert!((1.0..2.0).contains(&f));
+
+        let b = rng.gen_range(-100i8..100);
+        assert!((-100..100).contains(&b));
+    }
+}
+
+#[test]
+fn rng_point_in_rect() {
+    let mut rng = Rng::new(7);
+    let rect = shape::rect(20, 10);
+
+    for _ in 0..1000 {
+        let point = rng.point_in(Point(100, 100), &rect);
+        assert!(rect.contains(Point(100, 100), point, 0.0));
+    }
+}
+
+#[test]
+fn rng_gen_bool_extremes() {
+    let mut rng = Rng::new(7);
+
+    for _ in 0..100 {
+        assert!(!rng.gen_bool(0.0));
+        assert!(rng.gen_bool(1.0));
+    }
+}
+
+#[test]
+fn cooldown() {
+    let mut cooldown = Cooldown::new();
+    assert!(cooldown.ready());
+
+    cooldown.start(300);
+    cooldown.tick(299);
+    assert!(!cooldown.ready());
+    assert_eq!(cooldown.remaining(), 1);
+
+    cooldown.tick(10);
+    assert!(cooldown.ready());
+    assert_eq!(cooldown.remaining(), 0);
+}
+
+#[test]
+fn timer_carries_remainder() {
+    let mut timer = Timer::new(100);
+
+    assert_eq!(timer.tick(60), 0);
+    assert_eq!(timer.tick(60), 1);
+    assert_eq!(timer.tick(80), 1);
+    assert_eq!(timer.tick(350), 3);
+    assert_eq!(timer.tick(49), 0);
+    assert_eq!(timer.tick(1), 1);
+}
+
+#[test]
+fn timer_reset() {
+    let mut timer = Timer::new(100);
+    timer.tick(99);
+    timer.reset();
+    assert_eq!(timer.tick(99), 0);
+}
+
+#[test]
+fn camera_follow_eases_toward_target() {
+    let mut camera = Camera::new(Point(0, 0));
+
+    camera.follow(Point(100, 0), 1000, 0.25);
+    assert_eq!(camera.pos(), Point(75, 0));
+
+    camera.follow(Point(100, 0), 0, 0.25);
+    assert_eq!(camera.pos(), Point(75, 0));
+
+    camera.follow(Point(100, -40), 500, 0.0);
+    assert_eq!(camera.pos(), Point(100, -40));
+    assert_eq!(camera.offset(), Point(-100, 40));
+    assert_eq!(camera.to_world(Point(10, 10)), Point(110, -30));
+}
+
+#[test]
+fn camera_deadzone_and_bounds() {
+    let mut camera = Camera::new(Point(0, 0));
+    camera.deadzone = Some(shape::rect(40, 20));
+
+    // Inside the deadzone
+    camera.follow(Point(15, -5), 1000, 0.0);
+    assert_eq!(camera.pos(), Point(0, 0));
+
+    // Just far enough to keep the target at the edge
+    camera.follow(Point(50, 0), 1000, 0.0);
+    assert_eq!(camera.pos(), Point(30, 0));
+
+    camera.deadzone = None;
+    camera.view = shape::rect(200, 100);
+    camera.bounds = Some((Point(0, 0), shape::rect(400, 50)));
+
+    camera.follow(Point(500, 80), 1000, 0.0);
+    assert_eq!(camera.pos(), Point(100, 0));
+
+    camera.snap(Point(-500, 0));
+    assert_eq!(camera.pos(), Point(-100, 0));
+}
@@ -0,0 +1,42 @@
+use genji::{graphics::Viewport, shape::Point, util::Camera};
+
+fn camera() -> Camera {
+    Camera::new(Point(0, 0))
+}
+
+#[test]
+fn full_covers_the_screen() {
+    assert_eq!(
+        Viewport::full(camera()).pixels((800, 600)),
+        (0, 0, 800, 600)
+    );
+}
+
+#[test]
+fn vertical_split_is_left_then_right() {
+    let [left, right] = Viewport::split_vertical(camera(), camera());
+    assert_eq!(left.pixels((800, 600)), (0, 0, 400, 600));
+    assert_eq!(right.pixels((800, 600)), (400, 0, 400, 600));
+}
+
+#[test]
+fn horizontal_split_measures_from_the_bottom() {
+    let [top, bottom] = Viewport::split_horizontal(camera(), camera());
+    assert_eq!(top.pixels((800, 600)), (0, 300, 800, 300));
+    assert_eq!(bottom.pixels((800, 600)), (0, 0, 800, 300));
+}
+
+#[test]
+fn odd_sizes_leave_no_gap() {
+    let [left, right] = Viewport::split_vertical(camera(), camera());
+    let (l, _, lw, _) = left.pixels((801, 600));
+    let (r, _, rw, _) = right.pixels((801, 600));
+    assert_eq!(l + lw, r);
+    assert_eq!(lw + rw, 801);
+}
+
+#[test]
+fn clamped_to_the_screen() {
+    let viewport = Viewport::new((0.5, -0.5, 1.0, 1.0), camera());
+    assert_eq!(viewport.pixels((100, 100)), (50, 50, 50, 50));
+}
@@ -0,0 +1,153 @@
+use genji::prelude::*;
+
+#[test]
+fn query_ordered_sorts_by_order() {
+    let mut world = World::new();
+    let c = world.spawn(("c", Order(3)));
+    let a = world.spawn(("a", Order(1)));
+    let none = world.spawn(("none",));
+    let b = world.spawn(("b", Order(2)));
+
+    // Moving an entity to another archetype shouldn't change its place.
+    world.insert_one(a, 1.0f32).unwrap();
+
+    let ids: Vec<Entity> = world
+        .query_ordered::<&&str>()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    assert_eq!(ids, [none, a, b, c]);
+}
+
+#[test]
+fn query_ordered_breaks_ties_by_id() {
+    let mut world = World::new();
+    let first = world.spawn((Order(0),));
+    let second = world.spawn((Order(0), 5u8));
+
+    let ids: Vec<Entity> = world
+        .query_ordered::<&Order>()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    assert_eq!(ids, [first, second]);
+}
+
+#[test]
+fn query_ordered_can_borrow_order_mutably() {
+    let mut world = World::new();
+    world.spawn((Order(5),));
+    world.spawn((Order(1),));
+
+    for (i, (_, order)) in world.query_ordered::<&mut Order>().into_iter().enumerate() {
+        order.0 = i as u64 * 10;
+    }
+
+    let orders: Vec<u64> = world
+        .query_ordered::<&Order>()
+        .into_iter()
+        .map(|(_, order)| order.0)
+        .collect();
+    assert_eq!(orders, [0, 10]);
+}
+
+#[test]
+fn clear_matching_spares_kept_entities() {
+    let mut world = World::new();
+    let ui = world.spawn(("score", genji::ecs::Keep));
+    world.spawn(("enemy",));
+    world.spawn((Point(0, 0),));
+
+    assert_eq!(world.clear_matching::<()>(), 2);
+    assert_eq!(world.len(), 1);
+    assert!(world.contains(ui));
+}
+
+#[test]
+fn clear_matching_only_despawns_matches() {
+    let mut world = World::new();
+    world.spawn((Point(0, 0),));
+    world.spawn((Point(1, 1), genji::ecs::Keep));
+    let other = world.spawn(("no point",));
+
+    assert_eq!(world.clear_matching::<&Point>(), 1);
+    assert_eq!(world.len(), 2);
+    assert!(world.contains(other));
+}
+
+struct Enemy {
+    x: i32,
+    hp: u32,
+}
+
+struct Hp(u32);
+
+impl IntoSprite for Enemy {
+    type Bundle = (Rect, Point, Depth, Hp);
+
+    fn into_sprite(self) -> Self::Bundle {
+        (shape::rect(10, 10), Point(self.x, 0), Depth(2), Hp(self.hp))
+    }
+}
+
+#[test]
+fn spawn_sprite_spawns_the_bundle() {
+    let mut world = World::new();
+    let enemy = world.spawn_sprite(Enemy { x: 5, hp: 3 });
+
+    assert_eq!(*world.get::<&Point>(enemy).unwrap(), Point(5, 0));
+    assert_eq!(world.get::<&Hp>(enemy).unwrap().0, 3);
+    assert!(world.get::<&Rect>(enemy).is_ok());
+
+    let rect = world.spawn_sprite(shape::rect(4, 4).at(1, 2).depth(3));
+    assert_eq!(**world.get::<&Depth>(rect).unwrap(), 3);
+}
+
+#[test]
+fn pick_returns_frontmost_first() {
+    let mut world = World::new();
+    let back = world.spawn((shape::rect(20, 20), Point(0, 0), Depth(3)));
+    let front = world.spawn((shape::rect(20, 20), Point(0, 0), Depth(1)));
+    let tied = world.spawn((shape::rect(20, 20), Point(0, 0), Depth(1), Order(5)));
+    let top = world.spawn((shape::circle(4), Point(0, 0), Depth(9), AlwaysOnTop));
+    world.spawn((shape::rect(20, 20), Point(0, 0), Depth(0)));
+    world.spawn((shape::rect(20, 20),));
+
+    assert_eq!(world.pick(Point(0, 0)), [top, tied, front, back]);
+    assert_eq!(world.pick(Point(8, 8)), [tied, front, back]);
+    assert!(world.pick(Point(50, 50)).is_empty());
+}
+
+#[test]
+fn pick_breaks_ties_by_spawn_order() {
+    let mut world = World::new();
+    // Circles are queried after rects, but the later spawn is in front,
+    // just as it's drawn.
+    let circle = world.spawn((shape::circle(10), Point(0, 0)));
+    let rect = world.spawn((shape::rect(20, 20), Point(0, 0)));
+    let over = world.spawn((shape::circle(10), Point(0, 0)));
+
+    assert_eq!(world.pick(Point(0, 0)), [over, rect, circle]);
+}
+
+#[test]
+fn pick_ignores_depth_for_overlays() {
+    let mut world = World::new();
+    let shape = world.spawn((shape::rect(20, 20), Point(0, 0)));
+    let overlay = world.spawn((shape::rect(20, 20), Point(0, 0), Depth(0), AlwaysOnTop));
+
+    assert_eq!(world.pick(Point(0, 0)), [overlay, shape]);
+}
+
+#[test]
+fn pick_accounts_for_angle_and_parents() {
+    let mut world = World::new();
+    let parent = world.spawn((shape::rect(2, 2), Point(100, 0), Angle(90.0)));
+    let child = world.spawn((shape::rect(20, 4), Point(20, 0), Parent(parent)));
+
+    // Turned clockwise around the parent, the child hangs below it.
+    assert_eq!(world.pick(Point(100, -20)), [child]);
+    assert!(world.pick(Point(120, 0)).is_empty());
+}